@@ -0,0 +1,62 @@
+// Deposit addresses shown on the card. The wallet QR always restores the full wallet from the seed,
+// the deposit address only changes where the gifter sends the funds.
+use monero::cryptonote::subaddress::{self, Index};
+use monero::util::address::PaymentId;
+use monero::{Address, KeyPair, Network, PrivateKey, ViewPair};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DepositKind {
+    Primary,
+    Subaddress,
+    Integrated,
+}
+
+pub const DEPOSIT_KINDS: [(DepositKind, &str); 3] = [
+    (DepositKind::Primary, "Primary"),
+    (DepositKind::Subaddress, "Subaddress"),
+    (DepositKind::Integrated, "Integrated"),
+];
+
+// Private spend and view keys as hex, the way libmonero derives them
+pub fn keypair_from_hex(priv_sk: &str, priv_vk: &str) -> Result<KeyPair, String> {
+    let spend = PrivateKey::from_str(priv_sk).map_err(|_| "Invalid private spend key")?;
+    let view = PrivateKey::from_str(priv_vk).map_err(|_| "Invalid private view key")?;
+    Ok(KeyPair { view, spend })
+}
+
+pub fn primary(keys: &KeyPair) -> String {
+    Address::from_keypair(Network::Mainnet, keys).to_string()
+}
+
+pub fn subaddress(keys: &KeyPair, account: u32, index: u32) -> String {
+    let view_pair = ViewPair::from(*keys);
+    let index = Index {
+        major: account,
+        minor: index,
+    };
+    if index.is_zero() {
+        // 0/0 is the primary address itself
+        return primary(keys);
+    }
+    subaddress::get_subaddress(&view_pair, index, Some(Network::Mainnet)).to_string()
+}
+
+pub fn integrated(keys: &KeyPair, payment_id: &str) -> Result<String, String> {
+    let payment_id = hex::decode(payment_id.trim()).map_err(|_| "Payment ID must be hex")?;
+    if payment_id.len() != 8 {
+        return Err("Payment ID must be 16 hex characters".to_string());
+    }
+    let primary = Address::from_keypair(Network::Mainnet, keys);
+    Ok(Address::integrated(
+        Network::Mainnet,
+        primary.public_spend,
+        primary.public_view,
+        PaymentId::from_slice(&payment_id),
+    )
+    .to_string())
+}
+
+pub fn random_payment_id() -> String {
+    hex::encode(rand::random::<[u8; 8]>())
+}
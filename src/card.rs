@@ -0,0 +1,745 @@
+use crate::address::DepositKind;
+use crate::choice;
+use crate::funding::format_xmr;
+use crate::guide;
+use crate::layout::{TextBox, TextWriter};
+use crate::text::FontSet;
+use crate::verify;
+use crate::{Asset, GemApp};
+use image::{imageops, RgbaImage};
+use image::{io::Reader as ImageReader, DynamicImage};
+use image::{EncodableLayout, GenericImage, GenericImageView, Rgba};
+use imageproc::drawing::{draw_filled_rect_mut, draw_hollow_rect_mut, draw_line_segment_mut};
+use imageproc::rect::Rect;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum CardStyle {
+    // Greeting, instructions and QR codes on one side
+    Single,
+    // Greeting on the front, everything needed to redeem on the back
+    DoubleSided,
+    // Seed panel folded inside a glued flap, only the greeting and address stay visible
+    FoldSeal,
+}
+
+pub const CARD_STYLES: [(CardStyle, &str); 3] = [
+    (CardStyle::Single, "Single Sided"),
+    (CardStyle::DoubleSided, "Double Sided"),
+    (CardStyle::FoldSeal, "Fold And Seal"),
+];
+
+// Where the address QR code ends up for each style, the wallet QR code never moves
+pub fn addr_region(style: CardStyle) -> (u32, u32, u32, u32) {
+    match style {
+        CardStyle::FoldSeal => verify::FOLD_ADDR_REGION,
+        _ => verify::QR_ADDR_REGION,
+    }
+}
+
+// Text areas of the card, the left column ends before the divider at x=575
+const BODY_BOX: TextBox = TextBox {
+    x: 60,
+    y: 160,
+    width: 505.0,
+    height: 260.0,
+};
+const MESSAGE_BOX: TextBox = TextBox {
+    x: 60,
+    y: 458,
+    width: 505.0,
+    height: 30.0,
+};
+const CONTACT_BOX: TextBox = TextBox {
+    x: 60,
+    y: 518,
+    width: 505.0,
+    height: 60.0,
+};
+const FROM_BOX: TextBox = TextBox {
+    x: 800,
+    y: 500,
+    width: 195.0,
+    height: 20.0,
+};
+// Back side of a double sided card, the right column matches the single card so the QR codes
+// stay where verify expects them
+const BACK_BODY_BOX: TextBox = TextBox {
+    x: 60,
+    y: 160,
+    width: 505.0,
+    height: 200.0,
+};
+const SEED_BOX: TextBox = TextBox {
+    x: 60,
+    y: 398,
+    width: 505.0,
+    height: 105.0,
+};
+const ADDRESS_BOX: TextBox = TextBox {
+    x: 60,
+    y: 538,
+    width: 505.0,
+    height: 45.0,
+};
+// Front side of a double sided card, the whole width is free
+const FRONT_MESSAGE_BOX: TextBox = TextBox {
+    x: 60,
+    y: 220,
+    width: 884.0,
+    height: 200.0,
+};
+const FRONT_FROM_BOX: TextBox = TextBox {
+    x: 60,
+    y: 450,
+    width: 884.0,
+    height: 30.0,
+};
+const FRONT_TO_BOX: TextBox = TextBox {
+    x: 60,
+    y: 490,
+    width: 884.0,
+    height: 30.0,
+};
+const FRONT_CONTACT_BOX: TextBox = TextBox {
+    x: 60,
+    y: 540,
+    width: 660.0,
+    height: 30.0,
+};
+// Fold-and-seal card: the greeting panel (left of the divider), the seed panel (up to x=1004,
+// like the single card) and a flap that folds over the seed panel and gets glued down
+const FOLD_WIDTH: u32 = 1434;
+const FOLD_FLAP_X: u32 = 1004;
+const FOLD_GLUE: u32 = 30;
+const FOLD_MESSAGE_BOX: TextBox = TextBox {
+    x: 60,
+    y: 210,
+    width: 505.0,
+    height: 110.0,
+};
+const FOLD_FROM_BOX: TextBox = TextBox {
+    x: 60,
+    y: 335,
+    width: 320.0,
+    height: 25.0,
+};
+const FOLD_TO_BOX: TextBox = TextBox {
+    x: 60,
+    y: 365,
+    width: 320.0,
+    height: 25.0,
+};
+const FOLD_CONTACT_BOX: TextBox = TextBox {
+    x: 60,
+    y: 400,
+    width: 320.0,
+    height: 50.0,
+};
+const FOLD_NOTICE_BOX: TextBox = TextBox {
+    x: 70,
+    y: 475,
+    width: 300.0,
+    height: 50.0,
+};
+const FOLD_SEED_BOX: TextBox = TextBox {
+    x: 595,
+    y: 445,
+    width: 390.0,
+    height: 100.0,
+};
+const FOLD_BODY_BOX: TextBox = TextBox {
+    x: 1054,
+    y: 50,
+    width: 340.0,
+    height: 380.0,
+};
+const FOLD_STEPS_BOX: TextBox = TextBox {
+    x: 1054,
+    y: 450,
+    width: 340.0,
+    height: 100.0,
+};
+const TO_BOX: TextBox = TextBox {
+    x: 800,
+    y: 530,
+    width: 195.0,
+    height: 20.0,
+};
+
+fn load_template() -> Option<DynamicImage> {
+    let card = Asset::get("empty_card.png").unwrap();
+    let img = ImageReader::with_format(card.data.as_bytes(), image::ImageFormat::Png);
+    match image::load_from_memory(img.into_inner()) {
+        Ok(img) => Some(DynamicImage::ImageRgba8(img.to_rgba8())),
+        Err(_) => None,
+    }
+}
+
+// How to redeem the gift with the chosen guide, printed next to (or behind) the wallet QR code
+pub fn instructions(app: &GemApp, label: Option<&str>) -> String {
+    // A share QR code restores nothing in a wallet app, share cards explain the recombining instead
+    if let Some(label) = label {
+        return format!(
+            "This card is {} of a Monero gift of {} XMR (~{:.2} {}).\n\
+             On its own it can't restore the wallet, any {} of the {} share cards together can.\n\
+             1 - Collect {} share cards.\n\
+             2 - Read each big QR code with any QR reader, wallet apps can't use them.\n\
+             3 - In Gem, paste the gem-share: texts under \"Recombine Shares\", one per line, and press \"Recombine\".\n\
+             4 - Restore the recovered seed in any Monero wallet with the restore height {}.",
+            label,
+            app.amount,
+            app.value_xmr * app.amount,
+            app.currency.to_uppercase(),
+            app.share_threshold,
+            app.share_total,
+            app.share_threshold,
+            app.block_height
+        );
+    }
+    format!(
+        "Congratulations! You have been gifted {} XMR (~{:.2} {})\n\
+         You can redeem this gift at any time into a Monero wallet.\n\
+         {}\n\
+         {}\n\
+         After importing, you can use the XMR in the wallet as you wish.",
+        app.amount,
+        app.value_xmr * app.amount,
+        app.currency.to_uppercase(),
+        guide::intro(app.guide),
+        guide::steps(app.guide, app.secret, app.block_height).join("\n")
+    )
+}
+
+// Optional help QR code (120px) pointing at the redemption help page, labelled underneath
+fn draw_help_qr(img: &mut DynamicImage, fonts: &FontSet, app: &GemApp, x: u32, y: u32) {
+    if let Some(qr_help) = &app.qr_help {
+        for (qx, qy, pixel) in qr_help.pixels() {
+            let pixel = pixel.0[0];
+            img.put_pixel(qx + x, qy + y, Rgba([pixel, pixel, pixel, 255]));
+        }
+        fonts.draw(
+            img,
+            Rgba([0, 0, 0, 0]),
+            x as i32 + 37,
+            y as i32 + 122,
+            16.0,
+            "HELP",
+        );
+    }
+}
+
+// The seed words and/or private keys printed on the back or the seed panel. Labelled cards carry
+// a share in the wallet QR code, printing the wallet there would defeat the split.
+fn secret_text(app: &GemApp, label: Option<&str>) -> String {
+    if label.is_some() {
+        return "".to_string();
+    }
+    let seed = app.mnemonic.trim().to_string();
+    let keys = format!("Spend key: {}\nView key: {}", app.spend_key, app.view_key);
+    match app.secret {
+        guide::Secret::Seed => seed,
+        guide::Secret::Keys => keys,
+        guide::Secret::SeedAndKeys => format!("{}\n{}", seed, keys),
+    }
+}
+
+// The right column shared by the single card and the back side: both QR codes, the address label,
+// date, height, the optional label and the funding line
+fn draw_wallet_column(
+    img: &mut DynamicImage,
+    fonts: &FontSet,
+    app: &GemApp,
+    qr_main: &DynamicImage,
+    label: Option<&str>,
+) {
+    let black = Rgba([0, 0, 0, 0]);
+    let font_size = 20.0;
+    // The small QR code holds the deposit address, label it so the gifter funds the right one
+    let (address_label, address_label_x) = match app.deposit_kind {
+        DepositKind::Primary => ("ADDRESS".to_string(), 660),
+        DepositKind::Subaddress => (
+            format!(
+                "SUBADDRESS {}/{}",
+                app.deposit_subaddress.0, app.deposit_subaddress.1
+            ),
+            620,
+        ),
+        DepositKind::Integrated => ("INTEGRATED ADDR".to_string(), 620),
+    };
+    fonts.draw(img, black, 740, 30, 30.0, "WALLET");
+    fonts.draw(
+        img,
+        black,
+        address_label_x,
+        405,
+        font_size,
+        address_label.as_str(),
+    );
+    fonts.draw(
+        img,
+        black,
+        800,
+        440,
+        font_size,
+        format!("Date: {}", app.date.format("%d/%m/%Y")).as_str(),
+    );
+    fonts.draw(
+        img,
+        black,
+        800,
+        470,
+        font_size,
+        format!("Height: {}", app.block_height).as_str(),
+    );
+    // Only shown while the TXIDs field still holds the TXIDs that were verified against the node
+    if !app.verified_txids.is_empty() && app.verified_txids == app.txids {
+        fonts.draw(
+            img,
+            black,
+            800,
+            560,
+            font_size,
+            format!("Funded {}", format_xmr(app.verified_total)).as_str(),
+        );
+    }
+    draw_line_segment_mut(img, (575.0, 0.0), (575.0, 590.0), black);
+    if let Some(label) = label {
+        fonts.draw(img, black, 800, 410, font_size, label);
+    }
+    // draw qr code
+    for (x, y, pixel) in qr_main.pixels() {
+        let pixel = pixel.0[0];
+        let pixel = Rgba([pixel, pixel, pixel, 255]);
+        img.put_pixel(x + 615, y + 55, pixel);
+    }
+    // draw qr code
+    for (x, y, pixel) in app.qr_addr.pixels() {
+        let pixel = pixel.0[0];
+        let pixel = Rgba([pixel, pixel, pixel, 255]);
+        img.put_pixel(x + 620, y + 425, pixel);
+    }
+}
+
+// Draws the whole card for the current app state. `qr_main` is pasted as the big wallet QR code,
+// `label` is an optional extra line drawn above the date (e.g. "SHARE 2 OF 3").
+// Returns the card and warnings about text that didn't fit.
+pub fn render_card(
+    app: &GemApp,
+    qr_main: &DynamicImage,
+    label: Option<&str>,
+) -> Option<(DynamicImage, Vec<String>)> {
+    let fonts = &app.fonts;
+    let mut img = load_template()?;
+    let font_size = 20.0;
+    let black = Rgba([0, 0, 0, 0]);
+    let mut writer = TextWriter::new(fonts, black);
+    fonts.draw(&mut img, black, 160, 65, 60.0, "MONERO GIFT");
+    // Everything left of the divider at x=575 wraps inside these boxes
+    writer.text_box(
+        &mut img,
+        &BODY_BOX,
+        instructions(app, label).as_str(),
+        (font_size, 12.0),
+        "Instruction text",
+    );
+    // The help QR code takes the right end of the message and contact lines
+    let (message_box, contact_box) = match app.qr_help {
+        Some(_) => (
+            TextBox {
+                width: 375.0,
+                ..MESSAGE_BOX
+            },
+            TextBox {
+                width: 375.0,
+                ..CONTACT_BOX
+            },
+        ),
+        None => (MESSAGE_BOX, CONTACT_BOX),
+    };
+    fonts.draw(&mut img, black, 60, 430, font_size, "Message: ");
+    writer.text_box(
+        &mut img,
+        &message_box,
+        format!("- {}", app.description).as_str(),
+        (font_size, 12.0),
+        "Message",
+    );
+    fonts.draw(&mut img, black, 60, 490, font_size, "Contact:");
+    writer.text_box(
+        &mut img,
+        &contact_box,
+        format!("- {}", app.contact).as_str(),
+        (font_size, 12.0),
+        "Contact",
+    );
+    writer.text_box(
+        &mut img,
+        &FROM_BOX,
+        format!("From {}", app.from).as_str(),
+        (font_size, 10.0),
+        "From",
+    );
+    writer.text_box(
+        &mut img,
+        &TO_BOX,
+        format!("To {}", app.to).as_str(),
+        (font_size, 10.0),
+        "To",
+    );
+    draw_wallet_column(&mut img, fonts, app, qr_main, label);
+    draw_help_qr(&mut img, fonts, app, 445, 430);
+    Some((img, writer.warnings))
+}
+
+// Watch card for the gifter: the view-only QR code (`qr_watch`) next to the address QR code, with
+// the same details in plain text. Nothing on it can spend the gift.
+pub fn render_watch_card(
+    app: &GemApp,
+    qr_watch: &DynamicImage,
+    view_key: &str,
+) -> Option<(DynamicImage, Vec<String>)> {
+    let fonts = &app.fonts;
+    let mut img = load_template()?;
+    let font_size = 20.0;
+    let black = Rgba([0, 0, 0, 0]);
+    let mut writer = TextWriter::new(fonts, black);
+    fonts.draw(&mut img, black, 160, 75, 40.0, "WATCH ONLY");
+    let text = format!(
+        "This card is NOT the gift and can't spend it.\n\
+         It lets you follow the gift wallet sent to {}: scan the big QR code in Feather Wallet \
+         (New wallet > Restore from keys > Scan view-only details) to see the funds arrive.\n\
+         Address: {}\n\
+         Private view key: {}\n\
+         Restore height: {}",
+        if app.to.is_empty() {
+            "the recipient"
+        } else {
+            app.to.as_str()
+        },
+        app.address,
+        view_key,
+        app.block_height
+    );
+    writer.text_box(
+        &mut img,
+        &BODY_BOX,
+        text.as_str(),
+        (font_size, 8.0),
+        "Watch card text",
+    );
+    fonts.draw(&mut img, black, 700, 30, 30.0, "VIEW ONLY");
+    fonts.draw(&mut img, black, 660, 405, font_size, "ADDRESS");
+    fonts.draw(
+        &mut img,
+        black,
+        800,
+        440,
+        font_size,
+        format!("Date: {}", app.date.format("%d/%m/%Y")).as_str(),
+    );
+    fonts.draw(
+        &mut img,
+        black,
+        800,
+        470,
+        font_size,
+        format!("Height: {}", app.block_height).as_str(),
+    );
+    draw_line_segment_mut(&mut img, (575.0, 0.0), (575.0, 590.0), black);
+    for (x, y, pixel) in qr_watch.pixels() {
+        let pixel = pixel.0[0];
+        img.put_pixel(x + 615, y + 55, Rgba([pixel, pixel, pixel, 255]));
+    }
+    for (x, y, pixel) in app.qr_addr.pixels() {
+        let pixel = pixel.0[0];
+        img.put_pixel(x + 620, y + 425, Rgba([pixel, pixel, pixel, 255]));
+    }
+    Some((img, writer.warnings))
+}
+
+// Front of a double sided card: only the greeting, safe to display
+pub fn render_front(app: &GemApp) -> Option<(DynamicImage, Vec<String>)> {
+    let fonts = &app.fonts;
+    let mut img = load_template()?;
+    let black = Rgba([0, 0, 0, 0]);
+    let mut writer = TextWriter::new(fonts, black);
+    fonts.draw(&mut img, black, 160, 65, 60.0, "MONERO GIFT");
+    fonts.draw(
+        &mut img,
+        black,
+        60,
+        160,
+        40.0,
+        format!("{} XMR", app.amount).as_str(),
+    );
+    let message_box = match app.qr_help {
+        Some(_) => TextBox {
+            width: 780.0,
+            ..FRONT_MESSAGE_BOX
+        },
+        None => FRONT_MESSAGE_BOX,
+    };
+    writer.text_box(
+        &mut img,
+        &message_box,
+        app.description.as_str(),
+        (36.0, 14.0),
+        "Message",
+    );
+    writer.text_box(
+        &mut img,
+        &FRONT_FROM_BOX,
+        format!("From {}", app.from).as_str(),
+        (26.0, 12.0),
+        "From",
+    );
+    writer.text_box(
+        &mut img,
+        &FRONT_TO_BOX,
+        format!("To {}", app.to).as_str(),
+        (26.0, 12.0),
+        "To",
+    );
+    writer.text_box(
+        &mut img,
+        &FRONT_CONTACT_BOX,
+        app.contact.as_str(),
+        (20.0, 12.0),
+        "Contact",
+    );
+    fonts.draw(
+        &mut img,
+        black,
+        800,
+        545,
+        20.0,
+        format!("{}", app.date.format("%d/%m/%Y")).as_str(),
+    );
+    draw_help_qr(&mut img, fonts, app, 864, 300);
+    Some((img, writer.warnings))
+}
+
+// Back of a double sided card: instructions, seed words and address next to the QR codes
+pub fn render_back(
+    app: &GemApp,
+    qr_main: &DynamicImage,
+    label: Option<&str>,
+) -> Option<(DynamicImage, Vec<String>)> {
+    let fonts = &app.fonts;
+    let mut img = load_template()?;
+    let font_size = 20.0;
+    let black = Rgba([0, 0, 0, 0]);
+    let mut writer = TextWriter::new(fonts, black);
+    fonts.draw(&mut img, black, 160, 75, 40.0, "HOW TO REDEEM");
+    writer.text_box(
+        &mut img,
+        &BACK_BODY_BOX,
+        instructions(app, label).as_str(),
+        (font_size, 10.0),
+        "Instruction text on the back",
+    );
+    if label.is_none() {
+        let heading = format!("{}:", choice::name(&guide::SECRETS, app.secret));
+        fonts.draw(&mut img, black, 60, 370, font_size, heading.as_str());
+    }
+    writer.text_box(
+        &mut img,
+        &SEED_BOX,
+        secret_text(app, label).as_str(),
+        (18.0, 9.0),
+        "Secret on the back",
+    );
+    fonts.draw(&mut img, black, 60, 512, font_size, "Address:");
+    writer.text_box(
+        &mut img,
+        &ADDRESS_BOX,
+        app.deposit_address.as_str(),
+        (14.0, 8.0),
+        "Address on the back",
+    );
+    draw_wallet_column(&mut img, fonts, app, qr_main, label);
+    Some((img, writer.warnings))
+}
+
+fn draw_dashed_line(img: &mut DynamicImage, x: f32, color: Rgba<u8>) {
+    let mut y = 0.0;
+    while y < 590.0 {
+        draw_line_segment_mut(img, (x, y), (x, y + 12.0), color);
+        y += 24.0;
+    }
+}
+
+// Fold-and-seal card. Folding the flap over the seed panel and gluing it hides the seed, then the
+// sealed panel folds behind the greeting panel along the divider.
+pub fn render_fold(
+    app: &GemApp,
+    qr_main: &DynamicImage,
+    label: Option<&str>,
+) -> Option<(DynamicImage, Vec<String>)> {
+    let fonts = &app.fonts;
+    let mut img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+        FOLD_WIDTH,
+        590,
+        Rgba([255, 255, 255, 255]),
+    ));
+    imageops::overlay(&mut img, &load_template()?, 0, 0);
+    let font_size = 20.0;
+    let black = Rgba([0, 0, 0, 0]);
+    let glue = Rgba([215, 215, 215, 255]);
+    let mut writer = TextWriter::new(fonts, black);
+    // Greeting panel, the only side seen before the seal is broken
+    fonts.draw(&mut img, black, 160, 65, 60.0, "MONERO GIFT");
+    fonts.draw(
+        &mut img,
+        black,
+        60,
+        160,
+        30.0,
+        format!("{} XMR", app.amount).as_str(),
+    );
+    let message_box = match app.qr_help {
+        Some(_) => TextBox {
+            width: 375.0,
+            ..FOLD_MESSAGE_BOX
+        },
+        None => FOLD_MESSAGE_BOX,
+    };
+    writer.text_box(
+        &mut img,
+        &message_box,
+        app.description.as_str(),
+        (font_size, 12.0),
+        "Message",
+    );
+    writer.text_box(
+        &mut img,
+        &FOLD_FROM_BOX,
+        format!("From {}", app.from).as_str(),
+        (font_size, 10.0),
+        "From",
+    );
+    writer.text_box(
+        &mut img,
+        &FOLD_TO_BOX,
+        format!("To {}", app.to).as_str(),
+        (font_size, 10.0),
+        "To",
+    );
+    writer.text_box(
+        &mut img,
+        &FOLD_CONTACT_BOX,
+        app.contact.as_str(),
+        (16.0, 10.0),
+        "Contact",
+    );
+    draw_hollow_rect_mut(&mut img, Rect::at(60, 465).of_size(320, 70), black);
+    writer.text_box(
+        &mut img,
+        &FOLD_NOTICE_BOX,
+        "DO NOT ACCEPT IF SEAL IS BROKEN",
+        (22.0, 14.0),
+        "Seal notice",
+    );
+    fonts.draw(
+        &mut img,
+        black,
+        60,
+        550,
+        font_size,
+        format!("Date: {}", app.date.format("%d/%m/%Y")).as_str(),
+    );
+    let (x, y, _, _) = verify::FOLD_ADDR_REGION;
+    fonts.draw(
+        &mut img,
+        black,
+        x as i32,
+        y as i32 - 22,
+        font_size,
+        "ADDRESS",
+    );
+    for (qx, qy, pixel) in app.qr_addr.pixels() {
+        let pixel = pixel.0[0];
+        img.put_pixel(qx + x, qy + y, Rgba([pixel, pixel, pixel, 255]));
+    }
+    draw_help_qr(&mut img, fonts, app, 445, 200);
+    // Seed panel, hidden under the flap
+    fonts.draw(&mut img, black, 740, 30, 30.0, "WALLET");
+    for (x, y, pixel) in qr_main.pixels() {
+        let pixel = pixel.0[0];
+        img.put_pixel(x + 615, y + 55, Rgba([pixel, pixel, pixel, 255]));
+    }
+    fonts.draw(
+        &mut img,
+        black,
+        615,
+        410,
+        font_size,
+        format!("Height: {}", app.block_height).as_str(),
+    );
+    if let Some(label) = label {
+        fonts.draw(&mut img, black, 800, 410, font_size, label);
+    }
+    writer.text_box(
+        &mut img,
+        &FOLD_SEED_BOX,
+        secret_text(app, label).as_str(),
+        (16.0, 8.0),
+        "Secret on the seed panel",
+    );
+    if !app.verified_txids.is_empty() && app.verified_txids == app.txids {
+        fonts.draw(
+            &mut img,
+            black,
+            615,
+            555,
+            16.0,
+            format!("Funded {}", format_xmr(app.verified_total)).as_str(),
+        );
+    }
+    // Flap: glue along its edges, the instructions in the middle are read once it is opened
+    let flap_width = FOLD_WIDTH - FOLD_FLAP_X;
+    for rect in [
+        Rect::at(FOLD_FLAP_X as i32, 0).of_size(flap_width, FOLD_GLUE),
+        Rect::at(FOLD_FLAP_X as i32, 590 - FOLD_GLUE as i32).of_size(flap_width, FOLD_GLUE),
+        Rect::at((FOLD_WIDTH - FOLD_GLUE) as i32, 0).of_size(FOLD_GLUE, 590),
+    ] {
+        draw_filled_rect_mut(&mut img, rect, glue);
+    }
+    fonts.draw(&mut img, black, 1180, 5, 16.0, "GLUE");
+    fonts.draw(&mut img, black, 1180, 567, 16.0, "GLUE");
+    writer.text_box(
+        &mut img,
+        &FOLD_BODY_BOX,
+        instructions(app, label).as_str(),
+        (16.0, 8.0),
+        "Instruction text on the flap",
+    );
+    writer.text_box(
+        &mut img,
+        &FOLD_STEPS_BOX,
+        "1 - Fold this flap over the seed panel and glue it down.\n\
+         2 - Fold the sealed panel behind the greeting.\n\
+         3 - Put a seal sticker or tape over the open edge.",
+        (14.0, 8.0),
+        "Folding steps",
+    );
+    draw_dashed_line(&mut img, 575.0, black);
+    draw_dashed_line(&mut img, FOLD_FLAP_X as f32, black);
+    fonts.draw(&mut img, black, 580, 570, 14.0, "FOLD");
+    fonts.draw(&mut img, black, 1009, 570, 14.0, "FOLD");
+    Some((img, writer.warnings))
+}
+
+// The side holding the wallet QR code: the whole card, or its back when double sided
+pub fn render_wallet_side(
+    app: &GemApp,
+    qr_main: &DynamicImage,
+    label: Option<&str>,
+) -> Option<(DynamicImage, Vec<String>)> {
+    match app.card_style {
+        CardStyle::Single => render_card(app, qr_main, label),
+        CardStyle::DoubleSided => render_back(app, qr_main, label),
+        CardStyle::FoldSeal => render_fold(app, qr_main, label),
+    }
+}
@@ -0,0 +1,9 @@
+// Options picked from a fixed list in a combo box. Each module keeps its options as a
+// [(value, label); N] table, e.g. card::CARD_STYLES.
+pub fn name<T: PartialEq>(items: &[(T, &'static str)], value: T) -> &'static str {
+    items
+        .iter()
+        .find(|x| x.0 == value)
+        .map(|x| x.1)
+        .unwrap_or("")
+}
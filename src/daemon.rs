@@ -0,0 +1,223 @@
+// Monero daemon RPC access. Everything that talks to a node goes through the Daemon trait, so
+// scanning code can be used with another transport or a mocked node.
+use serde_json::json;
+
+pub const DEFAULT_NODE: &str = "http://xmr-node.cakewallet.com:18081";
+
+// Blocks taken off a height looked up by date, about a day at one block every two minutes. Block
+// timestamps come from miners' clocks and can be off by hours, the margin covers that.
+pub const DATE_HEIGHT_MARGIN: u64 = 720;
+
+pub struct DaemonTx {
+    pub hash: String,
+    pub blob: Vec<u8>,
+    // None while the transaction is still in the pool
+    pub height: Option<u64>,
+}
+
+pub trait Daemon {
+    fn block_count(&self) -> Result<u64, String>;
+    fn block_tx_hashes(&self, height: u64) -> Result<Vec<String>, String>;
+    // Unix time in seconds from the block header
+    fn block_timestamp(&self, height: u64) -> Result<u64, String>;
+    fn pool_tx_hashes(&self) -> Result<Vec<String>, String>;
+    fn transactions(&self, hashes: &[String]) -> Result<Vec<DaemonTx>, String>;
+}
+
+pub struct RpcDaemon {
+    pub url: String,
+    agent: ureq::Agent,
+}
+
+// HTTP agent for everything that leaves the machine. An invalid proxy is an error instead of a
+// silent direct connection.
+pub fn http_agent(proxy: &str) -> Result<ureq::Agent, String> {
+    let builder = ureq::AgentBuilder::new();
+    if proxy.trim().is_empty() {
+        return Ok(builder.build());
+    }
+    let proxy = ureq::Proxy::new(proxy.trim()).map_err(|e| format!("Invalid proxy: {}", e))?;
+    Ok(builder.proxy(proxy).build())
+}
+
+impl RpcDaemon {
+    pub fn new(url: &str, proxy: &str) -> Result<RpcDaemon, String> {
+        Ok(RpcDaemon {
+            url: url.trim().trim_end_matches('/').to_string(),
+            agent: http_agent(proxy)?,
+        })
+    }
+
+    fn json_rpc(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let resp = self
+            .agent
+            .post(format!("{}/json_rpc", self.url).as_str())
+            .set("Content-Type", "application/json")
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": "0",
+                "method": method,
+                "params": params
+            }))
+            .map_err(|e| e.to_string())?;
+        let resp: serde_json::Value = resp.into_json().map_err(|e| e.to_string())?;
+        if let Some(error) = resp.get("error") {
+            return Err(format!("Node error: {}", error["message"]));
+        }
+        Ok(resp["result"].clone())
+    }
+
+    fn other(&self, path: &str, body: serde_json::Value) -> Result<serde_json::Value, String> {
+        let resp = self
+            .agent
+            .post(format!("{}/{}", self.url, path).as_str())
+            .set("Content-Type", "application/json")
+            .send_json(body)
+            .map_err(|e| e.to_string())?;
+        resp.into_json().map_err(|e| e.to_string())
+    }
+}
+
+// Last block with a timestamp on or before `timestamp`, by binary search over block headers. Block
+// timestamps only roughly increase, so subtract DATE_HEIGHT_MARGIN before using it as a restore height.
+pub fn height_at(daemon: &impl Daemon, timestamp: u64) -> Result<u64, String> {
+    let mut low = 0;
+    let mut high = daemon.block_count()?.saturating_sub(1);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if daemon.block_timestamp(mid)? <= timestamp {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Ok(low)
+}
+
+fn string_list(value: &serde_json::Value) -> Vec<String> {
+    match value.as_array() {
+        Some(list) => list
+            .iter()
+            .filter_map(|x| x.as_str().map(|x| x.to_string()))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+impl Daemon for RpcDaemon {
+    fn block_count(&self) -> Result<u64, String> {
+        let result = self.json_rpc("get_block_count", json!({}))?;
+        result["count"]
+            .as_u64()
+            .ok_or("Node didn't return a block count".to_string())
+    }
+
+    fn block_tx_hashes(&self, height: u64) -> Result<Vec<String>, String> {
+        let result = self.json_rpc("get_block", json!({ "height": height }))?;
+        Ok(string_list(&result["tx_hashes"]))
+    }
+
+    fn block_timestamp(&self, height: u64) -> Result<u64, String> {
+        let result = self.json_rpc("get_block_header_by_height", json!({ "height": height }))?;
+        result["block_header"]["timestamp"]
+            .as_u64()
+            .ok_or("Node didn't return a block timestamp".to_string())
+    }
+
+    fn pool_tx_hashes(&self) -> Result<Vec<String>, String> {
+        let result = self.other("get_transaction_pool_hashes", json!({}))?;
+        Ok(string_list(&result["tx_hashes"]))
+    }
+
+    fn transactions(&self, hashes: &[String]) -> Result<Vec<DaemonTx>, String> {
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let result = self.other("get_transactions", json!({ "txs_hashes": hashes }))?;
+        let mut txs = Vec::new();
+        if let Some(list) = result["txs"].as_array() {
+            for tx in list {
+                let blob = hex::decode(tx["as_hex"].as_str().unwrap_or(""))
+                    .map_err(|_| "Node returned an invalid transaction")?;
+                let in_pool = tx["in_pool"].as_bool().unwrap_or(false);
+                txs.push(DaemonTx {
+                    hash: tx["tx_hash"].as_str().unwrap_or("").to_string(),
+                    blob,
+                    height: if in_pool {
+                        None
+                    } else {
+                        tx["block_height"].as_u64()
+                    },
+                });
+            }
+        }
+        Ok(txs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Blocks every two minutes from `start`, heights listed in `skewed` claim a time an hour early
+    struct Chain {
+        blocks: u64,
+        start: u64,
+        skewed: Vec<u64>,
+    }
+
+    impl Daemon for Chain {
+        fn block_count(&self) -> Result<u64, String> {
+            Ok(self.blocks)
+        }
+
+        fn block_tx_hashes(&self, _height: u64) -> Result<Vec<String>, String> {
+            Ok(Vec::new())
+        }
+
+        fn block_timestamp(&self, height: u64) -> Result<u64, String> {
+            assert!(height < self.blocks);
+            let timestamp = self.start + height * 120;
+            if self.skewed.contains(&height) {
+                return Ok(timestamp - 3600);
+            }
+            Ok(timestamp)
+        }
+
+        fn pool_tx_hashes(&self) -> Result<Vec<String>, String> {
+            Ok(Vec::new())
+        }
+
+        fn transactions(&self, _hashes: &[String]) -> Result<Vec<DaemonTx>, String> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn finds_last_block_on_or_before() {
+        let chain = Chain {
+            blocks: 100_000,
+            start: 1_000_000,
+            skewed: Vec::new(),
+        };
+        assert_eq!(height_at(&chain, 1_000_000 + 500 * 120).unwrap(), 500);
+        assert_eq!(height_at(&chain, 1_000_000 + 500 * 120 + 119).unwrap(), 500);
+        assert_eq!(height_at(&chain, 0).unwrap(), 0);
+        assert_eq!(height_at(&chain, u64::MAX).unwrap(), 99_999);
+    }
+
+    #[test]
+    fn skewed_timestamps_stay_within_margin() {
+        let chain = Chain {
+            blocks: 100_000,
+            start: 1_000_000,
+            skewed: (40_000..40_010).collect(),
+        };
+        let height = height_at(&chain, 1_000_000 + 40_000 * 120).unwrap();
+        assert!(height.abs_diff(40_000) < DATE_HEIGHT_MARGIN);
+    }
+}
@@ -0,0 +1,53 @@
+// Duplex print sheets for double sided cards: front and back placed on A4 pages so the back lands
+// behind the front once the printer turns the sheet over.
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Flip {
+    LongEdge,
+    ShortEdge,
+}
+
+pub const FLIPS: [(Flip, &str); 2] = [
+    (Flip::LongEdge, "Flip On Long Edge"),
+    (Flip::ShortEdge, "Flip On Short Edge"),
+];
+
+// A4 portrait at 300 DPI, the card images are 85x50mm at the same resolution
+pub const SHEET_WIDTH: u32 = 2480;
+pub const SHEET_HEIGHT: u32 = 3508;
+// 15mm, clear of the unprintable border of most printers
+const MARGIN: u32 = 177;
+
+fn blank_sheet() -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+        SHEET_WIDTH,
+        SHEET_HEIGHT,
+        Rgba([255, 255, 255, 255]),
+    ))
+}
+
+// The front goes in the top left corner. Turning the sheet over mirrors that position: across the
+// page for a long edge flip, top to bottom for a short edge flip. The short edge flip also turns
+// the back upside down, so it is rotated to read upright when the cut card is turned sideways.
+pub fn sheets(
+    front: &DynamicImage,
+    back: &DynamicImage,
+    flip: Flip,
+) -> (DynamicImage, DynamicImage) {
+    let mut front_sheet = blank_sheet();
+    imageops::overlay(&mut front_sheet, front, MARGIN as i64, MARGIN as i64);
+    let mut back_sheet = blank_sheet();
+    match flip {
+        Flip::LongEdge => {
+            let x = SHEET_WIDTH - MARGIN - back.width();
+            imageops::overlay(&mut back_sheet, back, x as i64, MARGIN as i64);
+        }
+        Flip::ShortEdge => {
+            let y = SHEET_HEIGHT - MARGIN - back.height();
+            imageops::overlay(&mut back_sheet, &back.rotate180(), MARGIN as i64, y as i64);
+        }
+    }
+    (front_sheet, back_sheet)
+}
@@ -0,0 +1,103 @@
+// User supplied entropy (dice rolls or coin flips) mixed with system randomness.
+// The derivation is documented in README.md so a seed can be reproduced independently:
+// seed = sc_reduce32(keccak256(system_entropy || user_entropy)), encoded as a 25 word mnemonic.
+use crate::wordlists;
+use curve25519_dalek::scalar::Scalar;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha3::{Digest, Keccak256};
+
+pub struct UserEntropy {
+    // Only the dice digits (1-6) or coin sides (H/T), without separators
+    pub normalized: String,
+    pub bits: f64,
+}
+
+pub fn parse_user_entropy(input: &str) -> Result<UserEntropy, String> {
+    let normalized = input
+        .chars()
+        .filter(|x| !x.is_whitespace() && *x != ',')
+        .map(|x| x.to_ascii_uppercase())
+        .collect::<String>();
+    if normalized.is_empty() {
+        return Err("No dice rolls or coin flips entered".to_string());
+    }
+    let bits = if normalized.chars().all(|x| ('1'..='6').contains(&x)) {
+        normalized.len() as f64 * 6f64.log2()
+    } else if normalized.chars().all(|x| x == 'H' || x == 'T') {
+        normalized.len() as f64
+    } else {
+        return Err("Enter either dice rolls (1-6) or coin flips (H/T), not both".to_string());
+    };
+    Ok(UserEntropy { normalized, bits })
+}
+
+pub fn system_entropy() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+pub fn derive_seed(system: &[u8; 32], user: &UserEntropy) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(system);
+    hasher.update(user.normalized.as_bytes());
+    let hash: [u8; 32] = hasher.finalize().into();
+    // Reduce modulo the curve order like Monero's sc_reduce32, so the seed is a valid spend key
+    Scalar::from_bytes_mod_order(hash).to_bytes()
+}
+
+// Monero's original 25 word encoding: every 4 bytes (little endian) become 3 words, the last word
+// is a checksum picked by the CRC32 of the word prefixes.
+pub fn mnemonic_from_seed(seed: &[u8; 32], language: &str) -> Result<Vec<String>, String> {
+    let wordset = wordlists::wordset(language)
+        .ok_or(format!("Seed language \"{}\" isn't supported", language))?;
+    let words = wordset.words();
+    let n = words.len() as u32;
+    let mut mnemonic = Vec::with_capacity(25);
+    for chunk in seed.chunks(4) {
+        let x = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        let w1 = x % n;
+        let w2 = (x / n + w1) % n;
+        let w3 = (x / n / n + w2) % n;
+        mnemonic.push(words[w1 as usize].to_string());
+        mnemonic.push(words[w2 as usize].to_string());
+        mnemonic.push(words[w3 as usize].to_string());
+    }
+    mnemonic.push(mnemonic[wordlists::checksum_index(&mnemonic, wordset)].clone());
+    Ok(mnemonic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mnemonics_decode_to_the_seed() {
+        let mut seed = [0u8; 32];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_mul(37).wrapping_add(11);
+        }
+        for wordset in wordlists::WORDSETS.iter() {
+            let mnemonic = mnemonic_from_seed(&seed, wordset.language).unwrap();
+            assert_eq!(mnemonic.len(), 25);
+            assert_eq!(
+                libmonero::keys::derive_hex_seed(mnemonic),
+                hex::encode(seed),
+                "{}",
+                wordset.language
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_languages() {
+        for language in ["", "de", "jp", "ja", "ru", "zh"] {
+            assert!(
+                mnemonic_from_seed(&[1u8; 32], language).is_err(),
+                "{}",
+                language
+            );
+        }
+    }
+}
@@ -0,0 +1,296 @@
+// Finds payments to a gift wallet with its private view key, so the gifter can confirm the funds
+// arrived before handing the card over.
+use crate::daemon::{Daemon, DaemonTx};
+use monero::{Transaction, ViewPair};
+use std::ops::Range;
+
+pub struct Payment {
+    pub txid: String,
+    // In piconero
+    pub amount: u64,
+    pub confirmations: u64,
+}
+
+pub struct FundingReport {
+    pub payments: Vec<Payment>,
+    pub scanned_blocks: u64,
+}
+
+impl FundingReport {
+    pub fn total(&self) -> u64 {
+        self.payments.iter().map(|x| x.amount).sum()
+    }
+
+    pub fn summary(&self) -> String {
+        if self.payments.is_empty() {
+            return format!(
+                "Nothing received yet ({} blocks scanned)",
+                self.scanned_blocks
+            );
+        }
+        let confirmations = self
+            .payments
+            .iter()
+            .map(|x| x.confirmations)
+            .min()
+            .unwrap_or(0);
+        format!(
+            "Received {} in {} transaction(s), {} confirmation(s)",
+            format_xmr(self.total()),
+            self.payments.len(),
+            confirmations
+        )
+    }
+}
+
+pub fn format_xmr(piconero: u64) -> String {
+    format!("{:.6} XMR", piconero as f64 / 1e12)
+}
+
+// Gift amounts are entered with 4 decimals, round there so f32 noise doesn't end up in piconero
+pub fn xmr_to_piconero(xmr: f32) -> u64 {
+    (xmr as f64 * 1e4).round() as u64 * 100_000_000
+}
+
+// Outputs in `txs` that belong to the wallet, `chain_height` is the daemon's block count
+pub fn owned_payments(
+    txs: &[DaemonTx],
+    view_pair: &ViewPair,
+    major: Range<u32>,
+    minor: Range<u32>,
+    chain_height: u64,
+) -> Vec<Payment> {
+    let mut payments = Vec::new();
+    for tx in txs {
+        let transaction = match monero::consensus::deserialize::<Transaction>(&tx.blob) {
+            Ok(transaction) => transaction,
+            Err(_) => continue,
+        };
+        let outputs = match transaction.check_outputs(view_pair, major.clone(), minor.clone()) {
+            Ok(outputs) => outputs,
+            Err(_) => continue,
+        };
+        if outputs.is_empty() {
+            continue;
+        }
+        let amount: u64 = outputs.iter().filter_map(|x| x.amount()).sum();
+        payments.push(Payment {
+            txid: tx.hash.clone(),
+            amount,
+            confirmations: match tx.height {
+                Some(height) => chain_height.saturating_sub(height),
+                None => 0,
+            },
+        });
+    }
+    payments
+}
+
+// Scans every block from `from_height` to the chain tip plus the transaction pool
+pub fn check_funding(
+    daemon: &impl Daemon,
+    view_pair: &ViewPair,
+    major: Range<u32>,
+    minor: Range<u32>,
+    from_height: u64,
+) -> Result<FundingReport, String> {
+    let chain_height = daemon.block_count()?;
+    let mut payments = Vec::new();
+    for height in from_height..chain_height {
+        let hashes = daemon.block_tx_hashes(height)?;
+        let txs = daemon.transactions(&hashes)?;
+        payments.extend(owned_payments(
+            &txs,
+            view_pair,
+            major.clone(),
+            minor.clone(),
+            chain_height,
+        ));
+    }
+    let txs = daemon.transactions(&daemon.pool_tx_hashes()?)?;
+    payments.extend(owned_payments(
+        &txs,
+        view_pair,
+        major.clone(),
+        minor.clone(),
+        chain_height,
+    ));
+    Ok(FundingReport {
+        payments,
+        scanned_blocks: chain_height.saturating_sub(from_height),
+    })
+}
+
+// Splits the comma separated TXIDs field, every TXID has to be 64 hex characters
+pub fn parse_txids(text: &str) -> Result<Vec<String>, String> {
+    let mut txids = Vec::new();
+    for txid in text.split(',') {
+        let txid = txid.trim().to_lowercase();
+        if txid.is_empty() {
+            continue;
+        }
+        if txid.len() != 64 || !txid.chars().all(|x| x.is_ascii_hexdigit()) {
+            return Err(format!("\"{}\" is not a valid TXID", txid));
+        }
+        txids.push(txid);
+    }
+    if txids.is_empty() {
+        return Err("No TXIDs entered".to_string());
+    }
+    Ok(txids)
+}
+
+// Fetches the given transactions and checks every one of them pays the wallet
+pub fn verify_txids(
+    daemon: &impl Daemon,
+    view_pair: &ViewPair,
+    major: Range<u32>,
+    minor: Range<u32>,
+    txids: &[String],
+) -> Result<FundingReport, String> {
+    let chain_height = daemon.block_count()?;
+    let txs = daemon.transactions(txids)?;
+    for txid in txids {
+        if !txs.iter().any(|x| &x.hash == txid) {
+            return Err(format!("TXID {}... not found on the node", &txid[..8]));
+        }
+    }
+    let payments = owned_payments(&txs, view_pair, major, minor, chain_height);
+    for txid in txids {
+        if !payments.iter().any(|x| &x.txid == txid) {
+            return Err(format!("TXID {}... doesn't pay this wallet", &txid[..8]));
+        }
+    }
+    Ok(FundingReport {
+        payments,
+        scanned_blocks: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::Daemon;
+    use monero::{PrivateKey, PublicKey};
+    use std::str::FromStr;
+
+    // Transaction from monero-rs's own tests, paying 0.007 XMR to subaddress 0/1 of the keys below
+    const TX_HEX: &str = "02000102000bb2e38c0189ea01a9bc02a533fe02a90705fd0540745f59f49374365304f8b4d5da63b444b2d74a40f8007ea44940c15cbbc80c9d106802000267f0f669ead579c1067cbffdf67c4af80b0287c549a10463122b4860fe215f490002b6a2e2f35a93d637ff7d25e20da326cee8e92005d3b18b3c425dabe8336568992c01d6c75cf8c76ac458123f2a498512eb65bb3cecba346c8fcfc516dc0c88518bb90209016f82359eb1fe71d604f0dce9470ed5fd4624bb9fce349a0e8317eabf4172f78a8b27dec6ea1a46da10ed8620fa8367c6391eaa8aabf4ebf660d9fe0eb7e9dfa08365a089ad2df7bce7ef776467898d5ca8947152923c54a1c5030e0c2f01035c555ff4285dcc44dfadd6bc37ec8b9354c045c6590446a81c7f53d8f199cace3faa7f17b3b8302a7cbb3881e8fdc23cca0275c9245fdc2a394b8d3ae73911e3541b10e7725cdeef5e0307bc218caefaafe97c102f39c8ce78f62cccf23c69baf0af55933c9d384ceaf07488f2f1ac7343a593449afd54d1065f6a1a4658845817e4b0e810afc4ca249096e463f9f368625fa37d5bbcbe87af68ce3c4d630f93a66defa4205b178f4e9fa04107bd535c7a4b2251df2dad255e470b611ffe00078c2916fc1eb2af1273e0df30dd1c74b6987b9885e7916b6ca711cbd4b7b50576e51af1439e9ed9e33eb97d8faba4e3bd46066a5026a1940b852d965c1db455d1401687ccaccc524e000b05966763564b7deb8fd64c7fb3d649897c94583dca1558893b071f5e6700dad139f3c6f973c7a43b207ee3e67dc7f7f18b52df442258200c7fe6d16685127da1df9b0d93d764c2659599bc6d300ae33bf8b7c2a504317da90ea2f0bb2af09bd531feae57cb4a0273d8add62fadfc6d43402372e5caf854e112b88417936f1a9c4045d48b5b0b7703d96801b35ff66c716cddbee1b92407aa069a162c163071710e28ccddf6fb560feea32485f2c54a477ae23fd8210427eabe4288cbe0ecbef4ed19ca049ceded424d9f839da957f56ffeb73060ea15498fcbc2d73606e85e963a667dafdb2641fb91862c07b98c1fdae8fadf514600225036dd63c22cdadb57d2125ebf30bc77f7ea0bc0dafb484bf01434954c5053b9c8a143f06972f80fa66788ea1e3425dc0104a9e3674729967b9819552ebb172418da0e4b3778ad4b3d6acd8f354ba09e54bbc8604540010e1e1e4d3066515aed457bd3399c0ce787236dbcd3923de4fb8faded10199b33c1251191612ab5526c1cf0cd55a0aeaed3f7a955ceced16dabdbeb0a2a19a9fdb5aa8c4fc8767cf70e4ad1838518bc6b9de7c420c1f57636579a14a5a8bdacd24e61a68adede8a2e07416c25409dd91ab78905bc99bab4ab4fb9e4ea628e09a271837769c4e67e580dcd5485e12e4e308cb4509686a7484a71f7dfe334499808c7122f07d45d89230b1f19ed86f675b7fec44ef5f3b178ae0af92ff114bd96baa264604fea5a762307bdce6cb483b7bc780d32ed5343fcc3aa306997f211dc075f6dfd66035c1db10bef8656fefbb45645264d401682e42fe3e05906f79d65481b87508f1a4c434e0d1dfc247d4276306f801a6b57e4e4a525177bae24e0bd88a216597d9db44f2604c29d8a5f74e7b934f55048690b5dcefd6489a81aa64c1edb49b320faab94130e603d99e455cfd828bca782176192ece95e9b967fe3dd698574cf0c0b6926970b156e1134658de657de42c4930e72b49c0d94da66c330ab188c10f0d2f578590f31bcac6fcff7e21f9ff67ae1a40d5a03b19301dcbbadc1aa9392795cf81f1401ec16d986a7f96fbb9e8e12ce04a2226e26b78117a4dfb757c6a44481ff68bb0909e7010988cd37146fb45d4cca4ba490aae323bb51a12b6864f88ea6897aa700ee9142eaf0880844083026f044a5e3dba4aae08578cb057976001beb27b5110c41fe336bf7879733739ce22fb31a1a6ac2c900d6d6c6facdbc60085e5c93d502542cfea90dbc62d4e061b7106f09f9c4f6c1b5506dd0550eb8b2bf17678b140de33a10ba676829092e6a13445d1857d06c715eea4492ff864f0b34d178a75a0f1353078f83cfee1440b0a20e64abbd0cab5c6e7083486002970a4904f8371805d1a0ee4aea8524168f0f39d2dfc55f545a98a031841a740e8422a62e123c8303021fb81afbb76d1120c0fbc4d3d97ba69f4e2fe086822ece2047c9ccea507008654c199238a5d17f009aa2dd081f7901d0688aa15311865a319ccba8de4023027235b5725353561c5f1185f6a063fb32fc65ef6e90339d406a6884d66be49d03daaf116ee4b65ef80dd3052a13157b929f98640c0bbe99c8323ce3419a136403dc3f7a95178c3966d2d7bdecf516a28eb2cf8cddb3a0463dc7a6248883f7be0a10aae1bb50728ec9b8880d6011b366a850798f6d7fe07103695dded3f371ca097c1d3596967320071d7f548938afe287cb9b8fae761fa592425623dcbf653028";
+    const TXID: &str = "30cd93c853eccd5bc17bbea248f872c76122ac29b5738717d2fd4d1d7557cd5f";
+    const VIEW_KEY: &str = "bcfdda53205318e1c14fa0ddca1a45df363bb427972981d0249d0f4652a7df07";
+    const SPEND_KEY: &str = "e5f4301d32f3bdaef814a835a18aaaa24b13cc76cf01a832a7852faf9322e907";
+
+    // A chain of `blocks` blocks with the transaction mined at `tx_height`, or in the pool if None
+    struct MockDaemon {
+        blocks: u64,
+        tx_height: Option<u64>,
+    }
+
+    impl Daemon for MockDaemon {
+        fn block_count(&self) -> Result<u64, String> {
+            Ok(self.blocks)
+        }
+
+        fn block_tx_hashes(&self, height: u64) -> Result<Vec<String>, String> {
+            assert!(height < self.blocks);
+            if self.tx_height == Some(height) {
+                return Ok(vec![TXID.to_string()]);
+            }
+            Ok(Vec::new())
+        }
+
+        fn block_timestamp(&self, height: u64) -> Result<u64, String> {
+            Ok(height * 120)
+        }
+
+        fn pool_tx_hashes(&self) -> Result<Vec<String>, String> {
+            if self.tx_height.is_none() {
+                return Ok(vec![TXID.to_string()]);
+            }
+            Ok(Vec::new())
+        }
+
+        fn transactions(&self, hashes: &[String]) -> Result<Vec<DaemonTx>, String> {
+            Ok(hashes
+                .iter()
+                .filter(|x| x.as_str() == TXID)
+                .map(|x| DaemonTx {
+                    hash: x.clone(),
+                    blob: hex::decode(TX_HEX).unwrap(),
+                    height: self.tx_height,
+                })
+                .collect())
+        }
+    }
+
+    fn view_pair() -> ViewPair {
+        let spend = PrivateKey::from_str(SPEND_KEY).unwrap();
+        ViewPair {
+            view: PrivateKey::from_str(VIEW_KEY).unwrap(),
+            spend: PublicKey::from_private_key(&spend),
+        }
+    }
+
+    #[test]
+    fn finds_mined_payment() {
+        let daemon = MockDaemon {
+            blocks: 100,
+            tx_height: Some(95),
+        };
+        let report = check_funding(&daemon, &view_pair(), 0..1, 0..2, 90).unwrap();
+        assert_eq!(report.scanned_blocks, 10);
+        assert_eq!(report.payments.len(), 1);
+        assert_eq!(report.payments[0].txid, TXID);
+        assert_eq!(report.payments[0].amount, 7_000_000_000);
+        assert_eq!(report.payments[0].confirmations, 5);
+        assert_eq!(report.total(), 7_000_000_000);
+    }
+
+    #[test]
+    fn finds_pool_payment() {
+        let daemon = MockDaemon {
+            blocks: 100,
+            tx_height: None,
+        };
+        let report = check_funding(&daemon, &view_pair(), 0..1, 0..2, 100).unwrap();
+        assert_eq!(report.scanned_blocks, 0);
+        assert_eq!(report.payments.len(), 1);
+        assert_eq!(report.payments[0].confirmations, 0);
+    }
+
+    #[test]
+    fn ignores_payment_before_start_and_other_subaddresses() {
+        let daemon = MockDaemon {
+            blocks: 100,
+            tx_height: Some(50),
+        };
+        let report = check_funding(&daemon, &view_pair(), 0..1, 0..2, 60).unwrap();
+        assert!(report.payments.is_empty());
+        assert_eq!(report.scanned_blocks, 40);
+        let daemon = MockDaemon {
+            blocks: 100,
+            tx_height: Some(95),
+        };
+        // The output goes to 0/1, scanning only the primary address misses it
+        let report = check_funding(&daemon, &view_pair(), 0..1, 0..1, 90).unwrap();
+        assert!(report.payments.is_empty());
+    }
+
+    #[test]
+    fn verifies_txids() {
+        let daemon = MockDaemon {
+            blocks: 100,
+            tx_height: Some(97),
+        };
+        let txids = parse_txids(format!(" {} ,", TXID.to_uppercase()).as_str()).unwrap();
+        let report = verify_txids(&daemon, &view_pair(), 0..1, 0..2, &txids).unwrap();
+        assert_eq!(report.total(), 7_000_000_000);
+        assert_eq!(report.payments[0].confirmations, 3);
+        let unknown = vec!["ab".repeat(32)];
+        assert!(verify_txids(&daemon, &view_pair(), 0..1, 0..2, &unknown).is_err());
+        // Paying another subaddress than the scanned ones doesn't count
+        assert!(verify_txids(&daemon, &view_pair(), 0..1, 0..1, &txids).is_err());
+        assert!(parse_txids("1234").is_err());
+        assert!(parse_txids(" , ").is_err());
+    }
+}
@@ -0,0 +1,143 @@
+// Redemption guides: the steps printed on the card and what the big QR code holds, per wallet.
+// Cake Wallet restores straight from the monero_wallet: URI, the other wallets take typed seed
+// words, so for them the QR code holds just the words and any QR reader can copy them.
+use crate::choice;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Guide {
+    Cake,
+    Feather,
+    Monerujo,
+    Stack,
+    Gui,
+    Cli,
+    // Any wallet: type the 25 words and the restore height
+    Generic,
+}
+
+pub const GUIDES: [(Guide, &str); 7] = [
+    (Guide::Cake, "Cake Wallet"),
+    (Guide::Feather, "Feather Wallet"),
+    (Guide::Monerujo, "Monerujo"),
+    (Guide::Stack, "Stack Wallet"),
+    (Guide::Gui, "Monero GUI"),
+    (Guide::Cli, "Monero CLI"),
+    (Guide::Generic, "Any Wallet"),
+];
+
+// What restores the wallet: the mnemonic, the private keys or both
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Secret {
+    Seed,
+    Keys,
+    SeedAndKeys,
+}
+
+pub const SECRETS: [(Secret, &str); 3] = [
+    (Secret::Seed, "Seed"),
+    (Secret::Keys, "Private Keys"),
+    (Secret::SeedAndKeys, "Seed And Keys"),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Payload {
+    // monero_wallet:<address>?seed=...&spend_key=...&view_key=...&height=...
+    WalletUri,
+    // The mnemonic alone
+    Seed,
+}
+
+// Keys always go in the URI, it is the only format that names them
+pub fn payload(guide: Guide, secret: Secret) -> Payload {
+    match (guide, secret) {
+        (Guide::Cake, _) => Payload::WalletUri,
+        (_, Secret::Seed) => Payload::Seed,
+        _ => Payload::WalletUri,
+    }
+}
+
+// The line introducing the steps
+pub fn intro(guide: Guide) -> String {
+    match guide {
+        Guide::Cake => "For example, you can use the instructions below for redeeming this gift into the Cake Wallet app:".to_string(),
+        Guide::Generic => "To redeem this gift into any Monero wallet:".to_string(),
+        _ => format!(
+            "For example, you can use the instructions below for redeeming this gift into {}:",
+            choice::name(&GUIDES, guide)
+        ),
+    }
+}
+
+// Numbered redemption steps, `height` is the restore height printed on the card. Cards with
+// both seed and keys are restored from the seed.
+pub fn steps(guide: Guide, secret: Secret, height: u64) -> Vec<String> {
+    let (restore_from, secret_step) = match secret {
+        Secret::Keys => (
+            "keys",
+            "Type the address, private spend key and private view key, scan the big QR code with any QR reader to copy them.",
+        ),
+        _ => (
+            "seed",
+            "Type the 25 seed words, scan the big QR code with any QR reader to copy them.",
+        ),
+    };
+    let height_step = format!("Enter {} as the restore height.", height);
+    let steps = match guide {
+        Guide::Cake => vec![
+            "Install and open the Cake Wallet app on your phone.".to_string(),
+            "Tap the 'Restore Wallet' button.".to_string(),
+            "Tap the 'Scan QR Code' button.".to_string(),
+            "Scan the big QR code on the side.".to_string(),
+        ],
+        Guide::Feather => vec![
+            "Install Feather Wallet from featherwallet.org and open it.".to_string(),
+            format!("Choose 'Restore wallet from {}'.", restore_from),
+            secret_step.to_string(),
+            height_step,
+        ],
+        Guide::Monerujo => vec![
+            "Install and open Monerujo on your Android phone.".to_string(),
+            format!("Tap '+' and choose 'Restore from {}'.", restore_from),
+            secret_step.to_string(),
+            height_step,
+        ],
+        Guide::Stack => vec![
+            "Install and open Stack Wallet on your phone.".to_string(),
+            "Tap 'Restore wallet' and choose Monero.".to_string(),
+            secret_step.to_string(),
+            format!(
+                "Set the restore height to {}, or the date on the card.",
+                height
+            ),
+        ],
+        Guide::Gui => vec![
+            "Install the Monero GUI from getmonero.org and open it.".to_string(),
+            "Choose 'Restore wallet from keys or mnemonic seed'.".to_string(),
+            secret_step.to_string(),
+            height_step,
+        ],
+        Guide::Cli => vec![
+            "Install the Monero CLI from getmonero.org.".to_string(),
+            match secret {
+                Secret::Keys => "Run: monero-wallet-cli --generate-from-keys gift".to_string(),
+                _ => "Run: monero-wallet-cli --restore-deterministic-wallet".to_string(),
+            },
+            secret_step.to_string(),
+            height_step,
+        ],
+        Guide::Generic => vec![
+            format!(
+                "Open any Monero wallet and choose to restore from {}.",
+                restore_from
+            ),
+            secret_step.to_string(),
+            height_step,
+        ],
+    };
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, x)| format!("{} - {}", i + 1, x))
+        .collect()
+}
@@ -0,0 +1,69 @@
+// Offline redemption help: a single static HTML page built from the same guide text as the card,
+// so the optional help QR code can point at it without any hosted service. It never contains the
+// seed or the address.
+use crate::guide;
+use crate::GemApp;
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Sketch of the card so the recipient knows which QR code to scan
+const CARD_SKETCH: &str = r##"<svg viewBox="0 0 1004 590" width="100%" role="img" aria-label="Gift card">
+<rect x="2" y="2" width="1000" height="586" rx="20" fill="#fff" stroke="#4c4c4c" stroke-width="4"/>
+<circle cx="98" cy="98" r="40" fill="#ff6600"/>
+<rect x="60" y="160" width="480" height="16" fill="#ccc"/>
+<rect x="60" y="200" width="420" height="16" fill="#ccc"/>
+<rect x="60" y="240" width="450" height="16" fill="#ccc"/>
+<line x1="575" y1="0" x2="575" y2="590" stroke="#4c4c4c" stroke-width="3"/>
+<rect x="615" y="55" width="350" height="350" fill="#ff6600" opacity="0.85"/>
+<text x="790" y="240" font-size="40" text-anchor="middle" fill="#fff" font-family="sans-serif">WALLET QR</text>
+<rect x="620" y="425" width="150" height="150" fill="#999"/>
+<text x="695" y="505" font-size="22" text-anchor="middle" fill="#fff" font-family="sans-serif">address</text>
+</svg>"##;
+
+pub fn page(app: &GemApp) -> String {
+    let steps = guide::steps(app.guide, app.secret, app.block_height)
+        .iter()
+        .map(|x| format!("<li>{}</li>", escape(x)))
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Redeem your Monero gift</title>
+<style>
+body {{ font-family: sans-serif; max-width: 40em; margin: 2em auto; padding: 0 1em; color: #222; line-height: 1.5; }}
+h1 {{ color: #ff6600; }}
+ol {{ list-style: none; padding: 0; }}
+li {{ border: 2px solid #ff6600; border-radius: 8px; margin: 1em 0; padding: 1em; font-size: 1.15em; }}
+.note {{ background: #f4f4f4; border-radius: 8px; padding: 1em; }}
+</style>
+</head>
+<body>
+<h1>You have been gifted {amount} XMR</h1>
+<p>You can redeem this gift at any time into a Monero wallet. {intro}</p>
+{sketch}
+<ol>
+{steps}
+</ol>
+<p>Restore height: <b>{height}</b>, card date: <b>{date}</b>.</p>
+<p class="note">The big QR code, the seed words and the private keys are the wallet itself. Anyone who sees them can spend the gift, so never photograph, share or type them anywhere except your own wallet app.</p>
+<p>After importing, you can use the XMR in the wallet as you wish.</p>
+</body>
+</html>
+"##,
+        amount = app.amount,
+        intro = escape(guide::intro(app.guide).as_str()),
+        sketch = CARD_SKETCH,
+        steps = steps,
+        height = app.block_height,
+        date = app.date.format("%d/%m/%Y"),
+    )
+}
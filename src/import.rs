@@ -0,0 +1,112 @@
+// Importing an existing wallet to print it: a JSON export (no .keys file needed), a text file with
+// the seed and restore height, or a picture of a wallet QR code. Everything ends up as a
+// WalletUri, the same shape the big QR code carries.
+use crate::project::PROJECT_EXTENSION;
+use crate::uri::WalletUri;
+use crate::verify;
+use std::path::Path;
+
+pub fn import_file(path: &Path) -> Result<WalletUri, String> {
+    let extension = path
+        .extension()
+        .map(|x| x.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "json" | PROJECT_EXTENSION => {
+            let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+            from_json(text.as_str())
+        }
+        "txt" | "" => {
+            let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+            from_text(text.as_str())
+        }
+        _ => from_image(path),
+    }
+}
+
+// Takes the field names of common exports, including Gem projects and Feather's key exports
+pub fn from_json(text: &str) -> Result<WalletUri, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let string = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|x| value[*x].as_str())
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+    };
+    let height = [
+        "restore_height",
+        "restoreHeight",
+        "height",
+        "block_height",
+        "blockheight",
+    ]
+    .iter()
+    .find_map(|x| {
+        value[*x]
+            .as_u64()
+            .or_else(|| value[*x].as_str().and_then(|x| x.trim().parse().ok()))
+    });
+    let wallet = WalletUri {
+        address: string(&["address", "primary_address", "primaryAddress"]).unwrap_or_default(),
+        seed: string(&["seed", "mnemonic", "mnemonic_seed"]),
+        spend_key: string(&[
+            "spend_key",
+            "spendKey",
+            "private_spend_key",
+            "privateSpendKey",
+        ]),
+        view_key: string(&["view_key", "viewKey", "private_view_key", "privateViewKey"]),
+        height,
+        txids: Vec::new(),
+    };
+    if wallet.seed.is_none() && wallet.spend_key.is_none() {
+        return Err("The JSON has no seed or private spend key".to_string());
+    }
+    Ok(wallet)
+}
+
+// A monero_wallet: URI, or lines holding the seed words (on one line or spread over several),
+// the restore height and optionally the address, with or without "Seed:" style labels
+pub fn from_text(text: &str) -> Result<WalletUri, String> {
+    if let Some(start) = text.find(crate::uri::WALLET_SCHEME) {
+        let uri = text[start..].split_whitespace().next().unwrap_or("");
+        return WalletUri::parse(uri);
+    }
+    let mut wallet = WalletUri::default();
+    let mut words = Vec::new();
+    for line in text.lines() {
+        let value = line.split_once(':').map(|x| x.1).unwrap_or(line).trim();
+        let tokens = value.split_whitespace().collect::<Vec<&str>>();
+        if tokens.len() == 1 && value.len() >= 95 && value.starts_with(['4', '8']) {
+            wallet.address = value.to_string();
+        } else if let Ok(height) = value.parse::<u64>() {
+            wallet.height = Some(height);
+        } else if !tokens.is_empty()
+            && tokens
+                .iter()
+                .all(|x| x.chars().all(|c| c.is_alphabetic() || c == '\''))
+        {
+            // Lojban seed words can have apostrophes
+            words.extend(tokens);
+        }
+    }
+    if words.len() != 25 {
+        return Err(format!(
+            "Found {} seed words instead of 25 in the text file",
+            words.len()
+        ));
+    }
+    wallet.seed = Some(words.join(" "));
+    Ok(wallet)
+}
+
+// The first QR code holding a wallet URI or bare seed words
+pub fn from_image(path: &Path) -> Result<WalletUri, String> {
+    let img = image::open(path).map_err(|e| e.to_string())?;
+    verify::decode_all(&img)
+        .iter()
+        .find_map(|x| from_text(x).ok())
+        .ok_or("No wallet QR code found in the image".to_string())
+}
@@ -0,0 +1,164 @@
+// Text layout for the card: measures shaped text with the font set, wraps text inside a box and
+// shrinks it until it fits.
+use crate::text::{is_rtl, FontSet};
+use image::{DynamicImage, Rgba};
+
+// Line height relative to the font size, the original card used 20px text every 30px
+const LINE_HEIGHT: f32 = 1.5;
+
+pub struct TextBox {
+    pub x: i32,
+    pub y: i32,
+    pub width: f32,
+    pub height: f32,
+}
+
+pub struct FittedText {
+    pub lines: Vec<String>,
+    pub size: f32,
+    // False if the text still didn't fit at the minimum size and got cut
+    pub fits: bool,
+}
+
+// Greedy word wrap, '\n' starts a new paragraph. Words wider than the box (or scripts without
+// spaces) are broken between characters.
+pub fn wrap(fonts: &FontSet, size: f32, text: &str, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let space_width = fonts.width(" ", size);
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0.0;
+        for word in paragraph.split_whitespace() {
+            // Each word is shaped once, the line width is the sum of its words and spaces
+            let word_width = fonts.width(word, size);
+            let width = if line.is_empty() {
+                word_width
+            } else {
+                line_width + space_width + word_width
+            };
+            if width <= max_width {
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                line_width = width;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(line);
+            }
+            line = String::new();
+            for c in word.chars() {
+                line.push(c);
+                if fonts.width(line.as_str(), size) > max_width && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(line);
+                    line = c.to_string();
+                }
+            }
+            line_width = fonts.width(line.as_str(), size);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn lines_height(lines: usize, size: f32) -> f32 {
+    if lines == 0 {
+        return 0.0;
+    }
+    (lines - 1) as f32 * size * LINE_HEIGHT + size
+}
+
+// Tries every size from `max_size` down to `min_size` until the wrapped text fits the box
+pub fn fit(
+    fonts: &FontSet,
+    text: &str,
+    text_box: &TextBox,
+    max_size: f32,
+    min_size: f32,
+) -> FittedText {
+    let mut size = max_size;
+    loop {
+        let lines = wrap(fonts, size, text, text_box.width);
+        if lines_height(lines.len(), size) <= text_box.height {
+            return FittedText {
+                lines,
+                size,
+                fits: true,
+            };
+        }
+        if size - 1.0 < min_size {
+            let mut lines = lines;
+            while lines.len() > 1 && lines_height(lines.len(), size) > text_box.height {
+                lines.pop();
+            }
+            return FittedText {
+                lines,
+                size,
+                fits: false,
+            };
+        }
+        size -= 1.0;
+    }
+}
+
+// Fits and draws the text, returns false if it had to be cut. Right-to-left lines are aligned
+// to the right edge of the box.
+fn draw_text_box(
+    img: &mut DynamicImage,
+    color: Rgba<u8>,
+    fonts: &FontSet,
+    text_box: &TextBox,
+    text: &str,
+    max_size: f32,
+    min_size: f32,
+) -> bool {
+    let fitted = fit(fonts, text, text_box, max_size, min_size);
+    for (i, line) in fitted.lines.iter().enumerate() {
+        let y = text_box.y + (i as f32 * fitted.size * LINE_HEIGHT) as i32;
+        let mut x = text_box.x;
+        if is_rtl(line.as_str()) {
+            x += (text_box.width - fonts.width(line.as_str(), fitted.size)).max(0.0) as i32;
+        }
+        fonts.draw(img, color, x, y, fitted.size, line.as_str());
+    }
+    fitted.fits
+}
+
+// Draws the text boxes of one card in one color and collects a warning for every text that had
+// to be cut
+pub struct TextWriter<'a> {
+    fonts: &'a FontSet,
+    color: Rgba<u8>,
+    pub warnings: Vec<String>,
+}
+
+impl<'a> TextWriter<'a> {
+    pub fn new(fonts: &'a FontSet, color: Rgba<u8>) -> Self {
+        Self {
+            fonts,
+            color,
+            warnings: Vec::new(),
+        }
+    }
+
+    // `sizes` are the largest and the smallest font size to try, `label` names the text in the
+    // warning
+    pub fn text_box(
+        &mut self,
+        img: &mut DynamicImage,
+        text_box: &TextBox,
+        text: &str,
+        sizes: (f32, f32),
+        label: &str,
+    ) {
+        let (max_size, min_size) = sizes;
+        if !draw_text_box(
+            img, self.color, self.fonts, text_box, text, max_size, min_size,
+        ) {
+            self.warnings
+                .push(format!("{} doesn't fit, it was cut", label));
+        }
+    }
+}
@@ -1,29 +1,69 @@
+use address::DepositKind;
+use card::CardStyle;
 use chrono::NaiveDate;
 use clipboard::ClipboardProvider;
+use core::f32;
+use daemon::Daemon;
 use eframe::egui;
-use image::{io::Reader as ImageReader, DynamicImage};
-use image::{EncodableLayout, GenericImage, GenericImageView, Luma, Rgba};
-use imageproc::drawing::{draw_line_segment_mut, draw_text_mut};
+use image::DynamicImage;
+use image::EncodableLayout;
 use libmonero::keys::{derive_hex_seed, derive_priv_keys, derive_pub_key, generate_seed};
-use qrcode::QrCode;
+use qrcode::EcLevel;
 use rfd::FileDialog;
 use rust_embed::RustEmbed;
-use rusttype::{Font, Scale};
-use serde_json::json;
-use core::f32;
-use std::ops::Div;
+use std::ops::Range;
+use std::sync::mpsc;
+
+mod address;
+mod card;
+mod choice;
+mod daemon;
+mod duplex;
+mod entropy;
+mod funding;
+mod guide;
+mod help;
+mod import;
+mod layout;
+mod preview;
+mod project;
+mod qr;
+mod reclaim;
+mod settings;
+mod shamir;
+mod text;
+mod uri;
+mod verify;
+mod wallet_rpc;
+mod watch;
+mod wordlists;
 
 #[derive(RustEmbed)]
 #[folder = "./embed/"]
 struct Asset;
 
+const USAGE: &str = "Usage: gem [--project <card.gem> --out <card.png|card.jpg>]";
+
 fn main() -> Result<(), eframe::Error> {
+    // With arguments Gem renders a project to an image instead of opening the window
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if !args.is_empty() {
+        match render_project(&args) {
+            Ok(status) => println!("{}", status),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_resizable(false)
+            .with_resizable(true)
             .with_fullscreen(false)
             .with_title("Gem - Gift Easily Monero")
-            .with_inner_size([1110.0, 700.0]),
+            .with_inner_size([1110.0, 700.0])
+            .with_min_inner_size([640.0, 480.0]),
         ..Default::default()
     };
     eframe::run_native(
@@ -52,7 +92,89 @@ struct GemApp {
     contact: String,
     booted: bool,
     img: DynamicImage,
+    preview_key: String,
+    preview: Option<(egui::TextureHandle, egui::Vec2)>,
     txids: String,
+    share_threshold: u8,
+    share_total: u8,
+    shares_text: String,
+    share_status: String,
+    user_entropy: String,
+    entropy_status: String,
+    save_status: String,
+    qr_ec_level: EcLevel,
+    deposit_kind: DepositKind,
+    sub_account: u32,
+    sub_index: u32,
+    // Account and index of the deposit address, the fields above only count once applied
+    deposit_subaddress: (u32, u32),
+    payment_id: String,
+    deposit_address: String,
+    node_url: String,
+    funding_status: String,
+    funding_rx: Option<mpsc::Receiver<Result<funding::FundingReport, String>>>,
+    txids_status: String,
+    txids_pending: Vec<String>,
+    txids_rx: Option<mpsc::Receiver<Result<funding::FundingReport, String>>>,
+    verified_txids: String,
+    verified_total: u64,
+    wallet_rpc_url: String,
+    fund_confirm: bool,
+    fund_status: String,
+    // Cards queued to be funded together, as (deposit address, piconero)
+    fund_batch: Vec<(String, u64)>,
+    fund_batch_confirm: bool,
+    // Destinations of the transfer in flight
+    fund_pending: Vec<(String, u64)>,
+    fund_rx: Option<mpsc::Receiver<Result<wallet_rpc::Transfer, String>>>,
+    reclaim_card: Option<reclaim::CardWallet>,
+    reclaim_to: String,
+    reclaim_status: String,
+    reclaim_rx: Option<mpsc::Receiver<Result<reclaim::Balance, String>>>,
+    sweep_rx: Option<mpsc::Receiver<Result<wallet_rpc::Transfer, String>>>,
+    height_status: String,
+    // Block found for the date and the restore height taken from it
+    height_rx: Option<mpsc::Receiver<Result<(u64, u64), String>>>,
+    reclaim_confirm: bool,
+    // Set once the loaded card's balance was checked, Sweep stays disabled until then
+    reclaim_checked: bool,
+    // Card wallets restored into wallet-rpc this session, Fund Card never spends from them
+    reclaimed: Vec<String>,
+    proxy: String,
+    currency: String,
+    language: String,
+    export_format: String,
+    saved_settings: settings::Settings,
+    project_include_wallet: bool,
+    project_status: String,
+    layout_warnings: String,
+    fallback_font: String,
+    fonts: text::FontSet,
+    font_status: String,
+    card_style: CardStyle,
+    duplex_flip: duplex::Flip,
+    preview_back: bool,
+    theme: preview::Theme,
+    print_preview: bool,
+    // Preview scale in points per card pixel, recomputed every frame while zoom_fit is set
+    zoom: f32,
+    zoom_fit: bool,
+    preview_gray: bool,
+    paper: preview::Paper,
+    screen_dpi: f32,
+    guide: guide::Guide,
+    help_url: String,
+    qr_help: Option<DynamicImage>,
+    help_status: String,
+    payment_uri: bool,
+    // What the address QR code holds, and what it was meant to hold before any fallback
+    address_qr_text: String,
+    address_qr_wanted: String,
+    secret: guide::Secret,
+    // Private keys as hex, derived from the mnemonic
+    spend_key: String,
+    view_key: String,
+    watch_status: String,
 }
 
 impl Default for GemApp {
@@ -75,42 +197,423 @@ impl Default for GemApp {
             to: "".to_string(),
             contact: "".to_string(),
             img: DynamicImage::new_rgb8(1, 1),
+            preview_key: "".to_string(),
+            preview: None,
             txids: "".to_string(),
+            share_threshold: 2,
+            share_total: 3,
+            shares_text: "".to_string(),
+            share_status: "".to_string(),
+            user_entropy: "".to_string(),
+            entropy_status: "".to_string(),
+            save_status: "".to_string(),
+            // Highest error correction survives wear on paper best
+            qr_ec_level: EcLevel::H,
+            deposit_kind: DepositKind::Primary,
+            sub_account: 0,
+            sub_index: 1,
+            deposit_subaddress: (0, 1),
+            payment_id: "".to_string(),
+            deposit_address: String::new(),
+            node_url: daemon::DEFAULT_NODE.to_string(),
+            funding_status: "".to_string(),
+            funding_rx: None,
+            txids_status: "".to_string(),
+            txids_pending: Vec::new(),
+            txids_rx: None,
+            verified_txids: "".to_string(),
+            verified_total: 0,
+            wallet_rpc_url: wallet_rpc::DEFAULT_WALLET_RPC.to_string(),
+            fund_confirm: false,
+            fund_status: "".to_string(),
+            fund_batch: Vec::new(),
+            fund_batch_confirm: false,
+            fund_pending: Vec::new(),
+            fund_rx: None,
+            reclaim_card: None,
+            reclaim_to: "".to_string(),
+            reclaim_status: "".to_string(),
+            reclaim_rx: None,
+            sweep_rx: None,
+            height_status: "".to_string(),
+            height_rx: None,
+            reclaim_confirm: false,
+            reclaim_checked: false,
+            reclaimed: Vec::new(),
+            proxy: "".to_string(),
+            currency: "usd".to_string(),
+            language: "en".to_string(),
+            export_format: "jpg".to_string(),
+            saved_settings: settings::Settings::default(),
+            project_include_wallet: false,
+            project_status: "".to_string(),
+            layout_warnings: "".to_string(),
+            fallback_font: "".to_string(),
+            fonts: text::FontSet::new(Vec::new()),
+            font_status: "".to_string(),
+            card_style: CardStyle::Single,
+            duplex_flip: duplex::Flip::LongEdge,
+            preview_back: false,
+            theme: preview::Theme::Dark,
+            print_preview: false,
+            zoom: 0.8,
+            zoom_fit: true,
+            preview_gray: false,
+            paper: preview::Paper::A4,
+            screen_dpi: 96.0,
+            guide: guide::Guide::Cake,
+            help_url: "".to_string(),
+            qr_help: None,
+            help_status: "".to_string(),
+            payment_uri: false,
+            address_qr_text: String::new(),
+            address_qr_wanted: String::new(),
+            secret: guide::Secret::Seed,
+            spend_key: String::new(),
+            view_key: String::new(),
+            watch_status: "".to_string(),
         }
     }
 }
 
+// Seed languages of the original 25 word mnemonic
+// The seed languages libmonero generates and decodes correctly, see wordlists.rs
+const LANGUAGES: [(&str, &str); 6] = [
+    ("en", "English"),
+    ("eo", "Esperanto"),
+    ("fr", "French"),
+    ("it", "Italian"),
+    ("lj", "Lojban"),
+    ("pt", "Portuguese"),
+];
+
+// libmonero panics on languages it doesn't know, so an old or edited settings or project file
+// falls back to English
+fn seed_language(language: &str) -> String {
+    match wordlists::wordset(language) {
+        Some(_) => language.to_string(),
+        None => "en".to_string(),
+    }
+}
+
+fn settings_from_app(self_app: &GemApp) -> settings::Settings {
+    settings::Settings {
+        node_url: self_app.node_url.clone(),
+        proxy: self_app.proxy.clone(),
+        wallet_rpc_url: self_app.wallet_rpc_url.clone(),
+        currency: self_app.currency.clone(),
+        language: self_app.language.clone(),
+        from: self_app.from.clone(),
+        contact: self_app.contact.clone(),
+        value_xmr: self_app.value_xmr,
+        auto_wallet: self_app.auto_wallet,
+        qr_ec_level: self_app.qr_ec_level,
+        export_format: self_app.export_format.clone(),
+        fallback_font: self_app.fallback_font.clone(),
+        card_style: self_app.card_style,
+        duplex_flip: self_app.duplex_flip,
+        guide: self_app.guide,
+        help_url: self_app.help_url.clone(),
+        payment_uri: self_app.payment_uri,
+        secret: self_app.secret,
+        theme: self_app.theme,
+        paper: self_app.paper,
+        screen_dpi: self_app.screen_dpi,
+    }
+}
+
+fn apply_settings(self_app: &mut GemApp, settings: settings::Settings) {
+    self_app.node_url = settings.node_url.clone();
+    self_app.proxy = settings.proxy.clone();
+    self_app.wallet_rpc_url = settings.wallet_rpc_url.clone();
+    self_app.currency = settings.currency.clone();
+    self_app.language = seed_language(settings.language.as_str());
+    self_app.from = settings.from.clone();
+    self_app.contact = settings.contact.clone();
+    self_app.value_xmr = settings.value_xmr;
+    self_app.auto_wallet = settings.auto_wallet;
+    self_app.qr_ec_level = settings.qr_ec_level;
+    self_app.export_format = settings.export_format.clone();
+    self_app.fallback_font = settings.fallback_font.clone();
+    self_app.card_style = settings.card_style;
+    self_app.duplex_flip = settings.duplex_flip;
+    self_app.guide = settings.guide;
+    self_app.help_url = settings.help_url.clone();
+    self_app.payment_uri = settings.payment_uri;
+    self_app.secret = settings.secret;
+    self_app.theme = settings.theme;
+    self_app.paper = settings.paper;
+    self_app.screen_dpi = settings.screen_dpi;
+    self_app.saved_settings = settings;
+}
+
+// Loads the fallback fonts for the card and the text fields, so names in other scripts show
+// up in both instead of boxes
+fn load_fonts(self_app: &mut GemApp, ctx: &egui::Context) {
+    let fallback_fonts = text::load_fallback_fonts(self_app.fallback_font.as_str());
+    let mut fonts = egui::FontDefinitions::default();
+    for (i, font) in fallback_fonts.iter().enumerate() {
+        let name = format!("fallback-{}", i);
+        fonts
+            .font_data
+            .insert(name.clone(), egui::FontData::from_owned(font.clone()));
+        for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+            fonts.families.entry(family).or_default().push(name.clone());
+        }
+    }
+    ctx.set_fonts(fonts);
+    set_card_fonts(self_app, fallback_fonts);
+}
+
+// MoneroGothic first, the fallback fonts cover whatever scripts and symbols it lacks
+fn set_card_fonts(self_app: &mut GemApp, fallback_fonts: Vec<Vec<u8>>) {
+    let gothic = Asset::get("MoneroGothic_v3.otf").unwrap();
+    let mut font_files = vec![gothic.data.into_owned()];
+    font_files.extend(fallback_fonts);
+    self_app.fonts = text::FontSet::new(font_files);
+    self_app.preview_key = "".to_string();
+}
+
+fn pick_fallback_font(self_app: &mut GemApp, ctx: &egui::Context) -> Result<String, String> {
+    let file = FileDialog::new()
+        .add_filter("Font", &["ttf", "otf", "ttc"])
+        .set_title("Pick Fallback Font")
+        .pick_file()
+        .ok_or("No file selected")?;
+    let bytes = std::fs::read(file.as_path()).map_err(|e| e.to_string())?;
+    if text::FontSet::new(vec![bytes]).is_empty() {
+        return Err("Not a usable font file".to_string());
+    }
+    self_app.fallback_font = file.display().to_string();
+    load_fonts(self_app, ctx);
+    Ok("Font loaded".to_string())
+}
+
+fn save_project(self_app: &GemApp) -> Result<String, String> {
+    let file = FileDialog::new()
+        .add_filter("Gem Project", &[project::PROJECT_EXTENSION])
+        .set_title("Save Project")
+        .set_file_name(format!("gem-project.{}", project::PROJECT_EXTENSION))
+        .save_file()
+        .ok_or("No file selected")?;
+    let include_wallet = self_app.project_include_wallet;
+    let project = project::Project {
+        description: self_app.description.clone(),
+        from: self_app.from.clone(),
+        to: self_app.to.clone(),
+        contact: self_app.contact.clone(),
+        amount: self_app.amount,
+        language: self_app.language.clone(),
+        card_style: self_app.card_style,
+        guide: self_app.guide,
+        help_url: self_app.help_url.clone(),
+        secret: self_app.secret,
+        include_wallet,
+        mnemonic: include_wallet.then(|| self_app.mnemonic.clone()),
+        address: include_wallet.then(|| self_app.address.clone()),
+        block_height: include_wallet.then_some(self_app.block_height),
+        txids: include_wallet.then(|| self_app.txids.clone()),
+    };
+    project::save(&project, file.as_path())?;
+    if include_wallet {
+        Ok("Project saved WITH the mnemonic, keep the file safe".to_string())
+    } else {
+        Ok("Project saved without wallet".to_string())
+    }
+}
+
+fn open_project(self_app: &mut GemApp) -> Result<String, String> {
+    let file = FileDialog::new()
+        .add_filter("Gem Project", &[project::PROJECT_EXTENSION])
+        .set_title("Open Project")
+        .pick_file()
+        .ok_or("No file selected")?;
+    let project = project::load(file.as_path())?;
+    Ok(apply_project(self_app, project))
+}
+
+fn apply_project(self_app: &mut GemApp, project: project::Project) -> String {
+    self_app.description = project.description;
+    self_app.from = project.from;
+    self_app.to = project.to;
+    self_app.contact = project.contact;
+    self_app.amount = project.amount;
+    self_app.language = seed_language(project.language.as_str());
+    self_app.card_style = project.card_style;
+    self_app.guide = project.guide;
+    self_app.help_url = project.help_url;
+    self_app.secret = project.secret;
+    update_help_qr(self_app);
+    if let Some(mnemonic) = project.mnemonic {
+        // A saved wallet is used as is, like a manually entered one
+        self_app.auto_wallet = false;
+        self_app.mnemonic = mnemonic;
+        self_app.address = project.address.unwrap_or_default();
+        self_app.block_height = project.block_height.unwrap_or(self_app.block_height);
+        self_app.txids = project.txids.unwrap_or_default();
+        update_qr_codes(self_app);
+        return "Project opened with its wallet".to_string();
+    }
+    "Project opened".to_string()
+}
+
+// gem --project card.gem --out card.png: renders a project saved with its wallet like "Save Card",
+// with the saved settings and without the window
+fn render_project(args: &[String]) -> Result<String, String> {
+    let mut project_file = None;
+    let mut out = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--project" => project_file = args.next(),
+            "--out" => out = args.next(),
+            _ => {
+                return Err(USAGE.to_string());
+            }
+        }
+    }
+    let (project_file, out) = match (project_file, out) {
+        (Some(project_file), Some(out)) => (project_file, std::path::PathBuf::from(out)),
+        _ => {
+            return Err(USAGE.to_string());
+        }
+    };
+    let mut self_app = GemApp::default();
+    apply_settings(&mut self_app, settings::load());
+    let fallback_fonts = text::load_fallback_fonts(self_app.fallback_font.as_str());
+    set_card_fonts(&mut self_app, fallback_fonts);
+    let project = project::load(std::path::Path::new(project_file))?;
+    let words = match &project.mnemonic {
+        Some(mnemonic) => mnemonic
+            .split_whitespace()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>(),
+        None => {
+            return Err(
+                "The project has no wallet, save it with \"Include wallet and mnemonic\""
+                    .to_string(),
+            );
+        }
+    };
+    wordlists::check_mnemonic(&words)?;
+    self_app.export_format = match out.extension().map(|x| x.to_string_lossy().to_lowercase()) {
+        Some(format) if format == "png" || format == "jpg" => format,
+        _ => {
+            return Err("The output has to be a .png or .jpg file".to_string());
+        }
+    };
+    apply_project(&mut self_app, project);
+    if derive_address_from_mnemonic(words) != self_app.address {
+        return Err("The project's address doesn't belong to its seed".to_string());
+    }
+    let payload = wallet_qr_payload(&self_app);
+    check_wallet_payload(&self_app, payload.as_str()).map_err(|e| format!("Not saved: {}", e))?;
+    write_card(&self_app, payload.as_str(), out)
+}
+
+// Fills the manual wallet fields from an exported wallet, seed file or QR code picture. Nothing
+// changes unless the seed (or spend key) derives the imported address and view key.
+fn import_wallet(self_app: &mut GemApp) -> Result<String, String> {
+    let file = FileDialog::new()
+        .add_filter(
+            "Wallet export",
+            &[
+                "json",
+                project::PROJECT_EXTENSION,
+                "txt",
+                "png",
+                "jpg",
+                "jpeg",
+            ],
+        )
+        .set_title("Import Wallet")
+        .pick_file()
+        .ok_or("No file selected")?;
+    let wallet = import::import_file(file.as_path())?;
+    let words = match (&wallet.seed, &wallet.spend_key) {
+        (Some(seed), _) => seed
+            .split_whitespace()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>(),
+        // The 25 word mnemonic is just the spend key written as words
+        (None, Some(spend_key)) => {
+            let spend_key: [u8; 32] = hex::decode(spend_key)
+                .ok()
+                .and_then(|x| x.try_into().ok())
+                .ok_or("Invalid private spend key")?;
+            entropy::mnemonic_from_seed(&spend_key, self_app.language.as_str())?
+        }
+        (None, None) => {
+            return Err("No seed or private spend key found".to_string());
+        }
+    };
+    // Misspelled words or an unsupported language are reported, libmonero would panic on them
+    wordlists::check_mnemonic(&words).map_err(|e| format!("{}, nothing imported", e))?;
+    let address = derive_address_from_mnemonic(words.clone());
+    if !wallet.address.is_empty() && wallet.address != address {
+        return Err("The imported address doesn't match the seed, nothing imported".to_string());
+    }
+    if let Some(view_key) = &wallet.view_key {
+        if !view_key.eq_ignore_ascii_case(derive_keys_from_mnemonic(words.clone()).1.as_str()) {
+            return Err(
+                "The imported view key doesn't match the seed, nothing imported".to_string(),
+            );
+        }
+    }
+    self_app.auto_wallet = false;
+    self_app.mnemonic = words.join(" ");
+    self_app.address = address;
+    if let Some(height) = wallet.height {
+        self_app.block_height = height;
+    }
+    if !wallet.txids.is_empty() {
+        self_app.txids = wallet.txids.join(",");
+    }
+    update_qr_codes(self_app);
+    match wallet.height {
+        Some(_) => Ok("Wallet imported, the address matches the seed".to_string()),
+        None => Ok(
+            "Wallet imported, the address matches the seed. No restore height found, set it by hand"
+                .to_string(),
+        ),
+    }
+}
+
 fn auto_fill(self_app: &mut GemApp, first: bool) {
-    // Get block height via ureq
-    let url = "http://xmr-node.cakewallet.com:18081/json_rpc";
-    let resp = ureq::post(url)
-        .set("Content-Type", "application/json")
-        .send_json(json!({
-            "jsonrpc": "2.0",
-            "id": "0",
-            "method": "get_block_count"
-        }));
-    // Check result
-    match resp {
-        Ok(resp) => {
-            let resp = resp.into_string().unwrap();
-            let resp: serde_json::Value = serde_json::from_str(resp.as_str()).unwrap();
-            let block_height = resp["result"]["count"].as_u64().unwrap();
-            self_app.block_height = block_height - 1000;
+    // Get block height from the node
+    let node = match daemon::RpcDaemon::new(self_app.node_url.as_str(), self_app.proxy.as_str()) {
+        Ok(node) => node,
+        Err(_) => {
+            return ();
+        }
+    };
+    match node.block_count() {
+        Ok(block_count) => {
+            self_app.block_height = block_count - 1000;
         }
         Err(_) => {
             return ();
         }
     }
     // Get price via coingecko
-    let url = "https://api.coingecko.com/api/v3/simple/price?ids=monero&vs_currencies=usd";
-    let resp = ureq::get(url).call();
+    let currency = self_app.currency.trim().to_lowercase();
+    let url = format!(
+        "https://api.coingecko.com/api/v3/simple/price?ids=monero&vs_currencies={}",
+        currency
+    );
+    let resp = match daemon::http_agent(self_app.proxy.as_str()) {
+        Ok(agent) => agent.get(url.as_str()).call(),
+        Err(_) => {
+            return ();
+        }
+    };
     match resp {
         Ok(resp) => {
             let resp = resp.into_string().unwrap();
             let resp: serde_json::Value = serde_json::from_str(resp.as_str()).unwrap();
-            let price = resp["monero"]["usd"].as_f64().unwrap();
-            self_app.value_xmr = price as f32;
+            if let Some(price) = resp["monero"][currency.as_str()].as_f64() {
+                self_app.value_xmr = price as f32;
+            }
         }
         Err(_) => {}
     };
@@ -120,573 +623,1708 @@ fn auto_fill(self_app: &mut GemApp, first: bool) {
     self_app.date = NaiveDate::parse_from_str(date.as_str(), "%d/%m/%Y").unwrap();
     // Generate wallet
     if first {
-        let mnemonic = generate_seed("en", "original");
-        let priv_keys = derive_priv_keys(derive_hex_seed(mnemonic.clone()));
-        let priv_sk = priv_keys[0].to_string();
-        let priv_vk = priv_keys[1].to_string();
-        let pub_sk = derive_pub_key(priv_sk);
-        let pub_vk = derive_pub_key(priv_vk);
-        let address = libmonero::keys::derive_address(pub_sk, pub_vk, 0);
-        self_app.address = address.clone();
-        self_app.mnemonic = mnemonic.join(" ");
-        let mne_str_encoded = mnemonic.join("%20");
-        let qr_code = QrCode::new(format!(
-            "monero_wallet:{}?seed={}&height={}",
-            address, mne_str_encoded, self_app.block_height
+        generate_wallet(self_app);
+    }
+}
+
+// Private spend and view keys as hex
+fn derive_keys_from_mnemonic(mnemonic: Vec<String>) -> (String, String) {
+    let priv_keys = derive_priv_keys(derive_hex_seed(mnemonic));
+    (priv_keys[0].to_string(), priv_keys[1].to_string())
+}
+
+fn derive_address_from_mnemonic(mnemonic: Vec<String>) -> String {
+    // We have to do all deriving manually for now, libmonero will support generating directly a wallet soon
+    let (priv_sk, priv_vk) = derive_keys_from_mnemonic(mnemonic);
+    let pub_sk = derive_pub_key(priv_sk);
+    let pub_vk = derive_pub_key(priv_vk);
+    libmonero::keys::derive_address(pub_sk, pub_vk, 0)
+}
+
+// Address the gifter should fund, derived from the same keys as the primary address
+fn wallet_keys(self_app: &GemApp) -> Result<monero::KeyPair, String> {
+    let words = self_app
+        .mnemonic
+        .split_whitespace()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>();
+    wordlists::check_mnemonic(&words)?;
+    let (priv_sk, priv_vk) = derive_keys_from_mnemonic(words);
+    address::keypair_from_hex(priv_sk.as_str(), priv_vk.as_str())
+}
+
+fn deposit_address(self_app: &GemApp) -> Result<String, String> {
+    if self_app.deposit_kind == DepositKind::Primary {
+        return Ok(self_app.address.clone());
+    }
+    let keys = wallet_keys(self_app)?;
+    match self_app.deposit_kind {
+        DepositKind::Subaddress => Ok(address::subaddress(
+            &keys,
+            self_app.sub_account,
+            self_app.sub_index,
+        )),
+        _ => address::integrated(&keys, self_app.payment_id.as_str()),
+    }
+}
+
+// Subaddress indexes to look for when scanning outputs
+fn scan_ranges(self_app: &GemApp) -> (Range<u32>, Range<u32>) {
+    // Integrated addresses receive on the primary keys, subaddresses up to the selected index
+    match self_app.deposit_kind {
+        DepositKind::Subaddress => {
+            let (account, index) = self_app.deposit_subaddress;
+            (0..account + 1, 0..index + 1)
+        }
+        _ => (0..1, 0..1),
+    }
+}
+
+// Checks the TXIDs field against the node in a background thread, only verified TXIDs get a
+// total printed on the card. The result is picked up in update().
+fn start_txids_check(self_app: &mut GemApp) {
+    self_app.verified_txids = "".to_string();
+    let txids = match funding::parse_txids(self_app.txids.as_str()) {
+        Ok(txids) => txids,
+        Err(e) => {
+            self_app.txids_status = e;
+            return ();
+        }
+    };
+    let keys = match wallet_keys(self_app) {
+        Ok(keys) => keys,
+        Err(e) => {
+            self_app.txids_status = e;
+            return ();
+        }
+    };
+    let view_pair = monero::ViewPair::from(keys);
+    let (major, minor) = scan_ranges(self_app);
+    let node = match daemon::RpcDaemon::new(self_app.node_url.as_str(), self_app.proxy.as_str()) {
+        Ok(node) => node,
+        Err(e) => {
+            self_app.txids_status = e;
+            return ();
+        }
+    };
+    self_app.txids_pending = txids.clone();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(funding::verify_txids(
+            &node, &view_pair, major, minor, &txids,
         ));
-        match qr_code {
-            Ok(qr_code) => {
-                let qr_img = qr_code.render::<Luma<u8>>().build();
-                let qr_img = DynamicImage::ImageLuma8(qr_img);
-                let qr_img = qr_img.resize_exact(350, 350, image::imageops::FilterType::Nearest);
-                self_app.qr_main = qr_img;
-            }
-            Err(_) => {
-                return ();
+    });
+    self_app.txids_status = "Verifying TXIDs...".to_string();
+    self_app.txids_rx = Some(rx);
+}
+
+// The current card as a (deposit address, piconero) destination
+fn fund_destination(self_app: &GemApp) -> Result<(String, u64), String> {
+    let amount = funding::xmr_to_piconero(self_app.amount);
+    if amount == 0 {
+        return Err("Gift amount is zero".to_string());
+    }
+    if self_app.deposit_address.is_empty() {
+        return Err("There is no address to fund".to_string());
+    }
+    Ok((self_app.deposit_address.clone(), amount))
+}
+
+// Queues the current card so several cards can be funded in one transfer later. Only the
+// deposit address and amount are kept, so the next wallet can be generated right away.
+fn add_to_fund_batch(self_app: &mut GemApp) -> Result<String, String> {
+    let destination = fund_destination(self_app)?;
+    if self_app.fund_batch.iter().any(|x| x.0 == destination.0) {
+        return Err("This card is already in the batch".to_string());
+    }
+    self_app.fund_batch.push(destination);
+    Ok(format!(
+        "{} card(s) in the batch, {} in total",
+        self_app.fund_batch.len(),
+        funding::format_xmr(self_app.fund_batch.iter().map(|x| x.1).sum())
+    ))
+}
+
+// Sends from the local wallet-rpc in a background thread, building the transaction can take a
+// while. The result is picked up in update().
+fn start_fund(self_app: &mut GemApp, destinations: Vec<(String, u64)>) {
+    let wallet = wallet_rpc::WalletRpc::new(self_app.wallet_rpc_url.as_str());
+    // After a reclaim wallet-rpc may still have a card wallet open instead of the hot wallet
+    let mut card_wallets = self_app.reclaimed.clone();
+    card_wallets.push(self_app.address.clone());
+    let pending = destinations.clone();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(wallet.address().and_then(|open| {
+            if card_wallets.contains(&open) {
+                return Err(
+                    "wallet-rpc has a card wallet open, open your hot wallet first".to_string(),
+                );
             }
+            wallet.transfer(&destinations)
+        }));
+    });
+    self_app.fund_status = format!("Sending to {} card(s)...", pending.len());
+    self_app.fund_pending = pending;
+    self_app.fund_rx = Some(rx);
+}
+
+// Sends the gift amount to the current card, its TXIDs field is filled once it went through
+fn fund_card(self_app: &mut GemApp) -> Result<String, String> {
+    let destination = fund_destination(self_app)?;
+    start_fund(self_app, vec![destination]);
+    Ok(self_app.fund_status.clone())
+}
+
+// Funds every queued card in one transfer_split call
+fn fund_batch(self_app: &mut GemApp) -> Result<String, String> {
+    if self_app.fund_batch.is_empty() {
+        return Err("The batch is empty".to_string());
+    }
+    start_fund(self_app, self_app.fund_batch.clone());
+    Ok(self_app.fund_status.clone())
+}
+
+fn load_reclaim_card(self_app: &mut GemApp) -> Result<String, String> {
+    let file = FileDialog::new()
+        .add_filter("Card", &["jpg", "jpeg", "png", "txt"])
+        .set_title("Load Card To Reclaim")
+        .pick_file()
+        .ok_or("No card selected")?;
+    let card = reclaim::load_card(file.as_path())?;
+    let status = format!(
+        "Loaded card for {}...",
+        card.address.chars().take(12).collect::<String>()
+    );
+    self_app.reclaim_card = Some(card);
+    self_app.reclaim_confirm = false;
+    self_app.reclaim_checked = false;
+    Ok(status)
+}
+
+// Restoring and refreshing can take a while, so it runs in a background thread like the funding check
+fn start_reclaim_balance(self_app: &mut GemApp) {
+    let card = match &self_app.reclaim_card {
+        Some(card) => card.clone(),
+        None => {
+            self_app.reclaim_status = "Load a card first".to_string();
+            return ();
+        }
+    };
+    let wallet = wallet_rpc::WalletRpc::new(self_app.wallet_rpc_url.as_str());
+    if !self_app.reclaimed.contains(&card.address) {
+        self_app.reclaimed.push(card.address.clone());
+    }
+    self_app.reclaim_checked = false;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(reclaim::restore_and_balance(&wallet, &card));
+    });
+    self_app.reclaim_status = "Restoring card wallet in wallet-rpc...".to_string();
+    self_app.reclaim_rx = Some(rx);
+}
+
+// Sweeping builds and relays a transaction, so it runs in a background thread too
+fn start_reclaim_sweep(self_app: &mut GemApp) {
+    let card = match &self_app.reclaim_card {
+        Some(card) if self_app.reclaim_checked => card.clone(),
+        _ => {
+            self_app.reclaim_status = "Check the card's balance first".to_string();
+            return ();
+        }
+    };
+    let wallet = wallet_rpc::WalletRpc::new(self_app.wallet_rpc_url.as_str());
+    let to = self_app.reclaim_to.clone();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(reclaim::sweep(&wallet, &card, to.as_str()));
+    });
+    self_app.reclaim_status = "Sweeping...".to_string();
+    self_app.sweep_rx = Some(rx);
+}
+
+// Scans the node for payments to the wallet with its private view key in a background thread,
+// the result is picked up in update()
+fn start_funding_check(self_app: &mut GemApp) {
+    let keys = match wallet_keys(self_app) {
+        Ok(keys) => keys,
+        Err(e) => {
+            self_app.funding_status = e;
+            return ();
+        }
+    };
+    let view_pair = monero::ViewPair::from(keys);
+    let (major, minor) = scan_ranges(self_app);
+    let node = match daemon::RpcDaemon::new(self_app.node_url.as_str(), self_app.proxy.as_str()) {
+        Ok(node) => node,
+        Err(e) => {
+            self_app.funding_status = e;
+            return ();
+        }
+    };
+    let from_height = self_app.block_height;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(funding::check_funding(
+            &node,
+            &view_pair,
+            major,
+            minor,
+            from_height,
+        ));
+    });
+    self_app.funding_status = "Checking funding...".to_string();
+    self_app.funding_rx = Some(rx);
+}
+
+// Looks up the restore height for the Date field in a background thread: the last block mined
+// before the date started (UTC), minus a safety margin. The result is picked up in update().
+fn start_height_lookup(self_app: &mut GemApp) {
+    let node = match daemon::RpcDaemon::new(self_app.node_url.as_str(), self_app.proxy.as_str()) {
+        Ok(node) => node,
+        Err(e) => {
+            self_app.height_status = e;
+            return ();
         }
-        let qr_addr_code = QrCode::new(format!("{}", address));
-        match qr_addr_code {
-            Ok(qr_addr_code) => {
-                let qr_addr_img = qr_addr_code.render::<Luma<u8>>().build();
-                let qr_addr_img = DynamicImage::ImageLuma8(qr_addr_img);
-                let qr_addr_img = qr_addr_img.resize_exact(150, 150, image::imageops::FilterType::Nearest);
-                self_app.qr_addr = qr_addr_img;
+    };
+    let timestamp = self_app
+        .date
+        .and_hms_opt(0, 0, 0)
+        .map(|x| x.and_utc().timestamp().max(0) as u64)
+        .unwrap_or(0);
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(
+            daemon::height_at(&node, timestamp)
+                .map(|block| (block, block.saturating_sub(daemon::DATE_HEIGHT_MARGIN))),
+        );
+    });
+    self_app.height_status = "Looking up height...".to_string();
+    self_app.height_rx = Some(rx);
+}
+
+fn generate_wallet(self_app: &mut GemApp) {
+    let mnemonic = if self_app.user_entropy.trim().is_empty() {
+        self_app.entropy_status = "".to_string();
+        generate_seed(self_app.language.as_str(), "original")
+    } else {
+        // Mix the dice rolls / coin flips with system randomness, see README.md for the derivation
+        let user = match entropy::parse_user_entropy(self_app.user_entropy.as_str()) {
+            Ok(user) => user,
+            Err(e) => {
+                self_app.entropy_status = e;
+                return ();
             }
-            Err(_) => {
+        };
+        let system = entropy::system_entropy();
+        self_app.entropy_status = format!(
+            "{:.1} bits from you, system entropy: {}",
+            user.bits,
+            hex::encode(system)
+        );
+        match entropy::mnemonic_from_seed(
+            &entropy::derive_seed(&system, &user),
+            self_app.language.as_str(),
+        ) {
+            Ok(mnemonic) => mnemonic,
+            Err(e) => {
+                self_app.entropy_status = e;
                 return ();
             }
         }
+    };
+    self_app.address = derive_address_from_mnemonic(mnemonic.clone());
+    self_app.mnemonic = mnemonic.join(" ");
+    update_qr_codes(self_app);
+}
+
+// Contents of the big wallet QR code: the seed alone or a monero_wallet: URI, depending on the
+// redemption guide. TXIDs are only added for manually entered wallets.
+fn wallet_qr_payload(self_app: &GemApp) -> String {
+    if guide::payload(self_app.guide, self_app.secret) == guide::Payload::Seed {
+        return self_app.mnemonic.trim().to_string();
+    }
+    let with_seed = self_app.secret != guide::Secret::Keys;
+    let with_keys = self_app.secret != guide::Secret::Seed && !self_app.spend_key.is_empty();
+    let txids = match self_app.auto_wallet {
+        true => Vec::new(),
+        false => self_app
+            .txids
+            .split(|x: char| x == ',' || x.is_whitespace())
+            .filter(|x| !x.is_empty())
+            .map(|x| x.to_string())
+            .collect(),
+    };
+    uri::WalletUri {
+        address: self_app.address.clone(),
+        seed: with_seed.then(|| {
+            self_app
+                .mnemonic
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ")
+        }),
+        spend_key: with_keys.then(|| self_app.spend_key.clone()),
+        view_key: with_keys.then(|| self_app.view_key.clone()),
+        height: Some(self_app.block_height),
+        txids,
+    }
+    .to_uri()
+}
+
+// The big QR code has to hold what "Card Holds" promises, e.g. keys are missing when the
+// mnemonic isn't 25 words long. A card without them can't restore anything.
+fn check_wallet_payload(self_app: &GemApp, payload: &str) -> Result<(), String> {
+    let (seed, keys) = if guide::payload(self_app.guide, self_app.secret) == guide::Payload::Seed {
+        (Some(payload.to_string()), false)
+    } else {
+        let wallet = uri::WalletUri::parse(payload)?;
+        let keys = wallet.spend_key.is_some_and(|x| !x.is_empty())
+            && wallet.view_key.is_some_and(|x| !x.is_empty());
+        (wallet.seed, keys)
+    };
+    let seed = seed.is_some_and(|x| x.split_whitespace().count() == 25);
+    let complete = match self_app.secret {
+        guide::Secret::Seed => seed,
+        guide::Secret::Keys => keys,
+        guide::Secret::SeedAndKeys => seed && keys,
+    };
+    if !complete {
+        return Err(format!(
+            "The wallet QR code lacks the {}, check the mnemonic",
+            choice::name(&guide::SECRETS, self_app.secret).to_lowercase()
+        ));
     }
+    Ok(())
+}
+
+// Everything the preview shows. The QR code images and the fonts aren't in it, whatever replaces
+// them clears the stored key instead.
+fn preview_key(self_app: &GemApp) -> String {
+    format!(
+        "{:?}",
+        (
+            (
+                self_app.mnemonic.as_str(),
+                self_app.spend_key.as_str(),
+                self_app.view_key.as_str(),
+                self_app.address.as_str(),
+                self_app.deposit_address.as_str(),
+                self_app.deposit_kind,
+                self_app.deposit_subaddress,
+                self_app.secret,
+                self_app.guide,
+            ),
+            (
+                self_app.description.as_str(),
+                self_app.from.as_str(),
+                self_app.to.as_str(),
+                self_app.contact.as_str(),
+                self_app.date,
+                self_app.block_height,
+                self_app.amount,
+                self_app.value_xmr,
+                self_app.currency.as_str(),
+            ),
+            (
+                self_app.txids.as_str(),
+                self_app.verified_txids.as_str(),
+                self_app.verified_total,
+                self_app.share_threshold,
+                self_app.share_total,
+                self_app.card_style,
+                self_app.preview_back,
+                self_app.preview_gray,
+            ),
+        )
+    )
+}
+
+fn update_qr_codes(self_app: &mut GemApp) {
+    let words = self_app
+        .mnemonic
+        .split_whitespace()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>();
+    // A typo in a typed seed is reported instead of reaching libmonero
+    (self_app.spend_key, self_app.view_key) = match wordlists::check_mnemonic(&words) {
+        Ok(()) => derive_keys_from_mnemonic(words),
+        Err(e) => {
+            if !words.is_empty() {
+                self_app.save_status = e;
+            }
+            (String::new(), String::new())
+        }
+    };
+    if let Some(qr_img) = qr::qr_image(
+        wallet_qr_payload(self_app).as_str(),
+        350,
+        self_app.qr_ec_level,
+    ) {
+        self_app.qr_main = qr_img;
+        self_app.preview_key = "".to_string();
+    } else {
+        self_app.save_status =
+            "Wallet QR code doesn't fit on the card, try a lower error correction".to_string();
+    }
+    self_app.deposit_address = match deposit_address(self_app) {
+        Ok(deposit_address) => {
+            self_app.deposit_subaddress = (self_app.sub_account, self_app.sub_index);
+            deposit_address
+        }
+        Err(e) => {
+            self_app.save_status = e;
+            self_app.deposit_kind = DepositKind::Primary;
+            self_app.address.clone()
+        }
+    };
+    update_address_qr(self_app);
+    update_help_qr(self_app);
+}
+
+// What the small QR code holds: the deposit address, or a payment URI that also fills in the
+// amount and the message in the gifter's wallet
+fn address_qr_payload(self_app: &GemApp) -> String {
+    if self_app.payment_uri {
+        uri::payment_uri(
+            self_app.deposit_address.as_str(),
+            self_app.amount,
+            self_app.description.as_str(),
+        )
+    } else {
+        self_app.deposit_address.clone()
+    }
+}
+
+fn update_address_qr(self_app: &mut GemApp) {
+    let mut payload = address_qr_payload(self_app);
+    self_app.address_qr_wanted = payload.clone();
+    let mut qr_addr_img = qr::qr_image(payload.as_str(), 150, self_app.qr_ec_level);
+    if qr_addr_img.is_none() && self_app.payment_uri {
+        self_app.save_status =
+            "Payment URI doesn't fit in the address QR code, using the bare address".to_string();
+        payload = self_app.deposit_address.clone();
+        qr_addr_img = qr::qr_image(payload.as_str(), 150, self_app.qr_ec_level);
+    }
+    if let Some(qr_addr_img) = qr_addr_img {
+        self_app.qr_addr = qr_addr_img;
+        self_app.preview_key = "".to_string();
+    }
+    self_app.address_qr_text = payload;
+}
+
+// The optional third QR code. Low error correction keeps the modules big enough to scan at
+// 120px, a damaged help link costs nothing.
+fn update_help_qr(self_app: &mut GemApp) {
+    self_app.preview_key = "".to_string();
+    let help_url = self_app.help_url.trim();
+    if help_url.is_empty() {
+        self_app.qr_help = None;
+        self_app.help_status = "".to_string();
+        return ();
+    }
+    self_app.qr_help = qr::qr_image(help_url, 120, EcLevel::L);
+    self_app.help_status = match self_app.qr_help {
+        Some(_) => "".to_string(),
+        None => "Help link is too long for its QR code".to_string(),
+    };
+}
+
+// Writes the redemption help page and, if no help link is set yet, links the help QR code to it
+fn export_help_page(self_app: &mut GemApp) -> Result<String, String> {
+    let file = FileDialog::new()
+        .add_filter("HTML", &["html"])
+        .set_title("Export Help Page")
+        .set_file_name("gem-redeem.html")
+        .save_file()
+        .ok_or("No file selected")?;
+    std::fs::write(file.as_path(), help::page(self_app)).map_err(|e| e.to_string())?;
+    if !self_app.help_url.trim().is_empty() {
+        return Ok(format!("Help page saved to {}", file.display()));
+    }
+    self_app.help_url = uri::file_uri(file.as_path());
+    update_help_qr(self_app);
+    Ok("Help page saved, the help QR code now points to it".to_string())
+}
+
+// Saves the card. Double sided cards are saved as front, back and the two duplex print sheets
+// next to the chosen file name.
+fn save_card(self_app: &GemApp) -> Result<String, String> {
+    let payload = wallet_qr_payload(self_app);
+    check_wallet_payload(self_app, payload.as_str()).map_err(|e| format!("Not saved: {}", e))?;
+    let date = chrono::Local::now();
+    let date = date.format("%d-%m-%Y-%H-%M").to_string();
+    let file = FileDialog::new()
+        .add_filter(
+            self_app.export_format.as_str(),
+            &[self_app.export_format.as_str()],
+        )
+        .set_title("Save Image")
+        .set_file_name(format!("gem-wallet-{}.{}", date, self_app.export_format))
+        .save_file()
+        .ok_or("No file selected")?;
+    write_card(self_app, payload.as_str(), file)
+}
+
+// Renders the card (or both sides and the duplex sheets) and writes it next to `file`
+fn write_card(
+    self_app: &GemApp,
+    payload: &str,
+    file: std::path::PathBuf,
+) -> Result<String, String> {
+    let (wallet_side, _) = card::render_wallet_side(self_app, &self_app.qr_main, None)
+        .ok_or("Couldn't render the card")?;
+    // Only save cards whose QR codes scan back to the current wallet
+    let wallet_bytes = verify::encode_verified(
+        &wallet_side,
+        self_app.export_format.as_str(),
+        payload,
+        self_app.address_qr_text.as_str(),
+        card::addr_region(self_app.card_style),
+    )
+    .map_err(|e| format!("Not saved: {}", e))?;
+    if self_app.card_style != CardStyle::DoubleSided {
+        std::fs::write(file, wallet_bytes).map_err(|e| e.to_string())?;
+        return Ok("Saved, QR codes verified".to_string());
+    }
+    let (front, _) = card::render_front(self_app).ok_or("Couldn't render the front")?;
+    let (front_sheet, back_sheet) = duplex::sheets(&front, &wallet_side, self_app.duplex_flip);
+    let stem = file.with_extension("");
+    let side_file = |side: &str| format!("{}-{}.{}", stem.display(), side, self_app.export_format);
+    let format = self_app.export_format.as_str();
+    std::fs::write(side_file("front"), verify::encode(&front, format)?)
+        .map_err(|e| e.to_string())?;
+    std::fs::write(side_file("back"), wallet_bytes).map_err(|e| e.to_string())?;
+    std::fs::write(side_file("duplex-1"), verify::encode(&front_sheet, format)?)
+        .map_err(|e| e.to_string())?;
+    std::fs::write(side_file("duplex-2"), verify::encode(&back_sheet, format)?)
+        .map_err(|e| e.to_string())?;
+    Ok(format!(
+        "Saved front, back and duplex sheets ({}), QR codes verified",
+        choice::name(&duplex::FLIPS, self_app.duplex_flip)
+    ))
+}
+
+// Saves the view-only details as JSON and as a watch card image next to it, for the gifter only
+fn save_watch_card(self_app: &GemApp) -> Result<String, String> {
+    // A typed address that isn't the seed's would give a watch wallet that never sees the gift
+    if !self_app.view_key.is_empty() {
+        let keys =
+            address::keypair_from_hex(self_app.spend_key.as_str(), self_app.view_key.as_str())?;
+        if address::primary(&keys) != self_app.address {
+            return Err("The address doesn't belong to the seed, watch card not saved".to_string());
+        }
+    }
+    let view_only = watch::ViewOnly::new(
+        self_app.address.as_str(),
+        self_app.view_key.as_str(),
+        self_app.block_height,
+    )?;
+    let file = FileDialog::new()
+        .add_filter("JSON", &["json"])
+        .set_title("Save Watch-Only Details")
+        .set_file_name(format!("{}.json", view_only.wallet_name))
+        .save_file()
+        .ok_or("No file selected")?;
+    watch::save_json(&view_only, file.as_path())?;
+    let json = view_only.to_json();
+    let qr_watch = qr::qr_image(json.as_str(), 350, self_app.qr_ec_level)
+        .ok_or("Couldn't create watch QR code")?;
+    let (img, _) = card::render_watch_card(self_app, &qr_watch, self_app.view_key.as_str())
+        .ok_or("Couldn't render the watch card")?;
+    let bytes = verify::encode_verified(
+        &img,
+        self_app.export_format.as_str(),
+        json.as_str(),
+        self_app.address_qr_text.as_str(),
+        verify::QR_ADDR_REGION,
+    )
+    .map_err(|e| format!("JSON saved, watch card not saved: {}", e))?;
+    let card_file = file.with_extension(self_app.export_format.as_str());
+    std::fs::write(card_file, bytes).map_err(|e| e.to_string())?;
+    Ok("Watch-only JSON and card saved".to_string())
+}
+
+// Splits the current mnemonic into Shamir shares and saves one card per share into a chosen folder
+fn save_share_cards(self_app: &GemApp) -> Result<String, String> {
+    let mnemonic = self_app.mnemonic.trim();
+    if mnemonic.is_empty() {
+        return Err("There is no mnemonic to split".to_string());
+    }
+    let shares = shamir::split(
+        mnemonic.as_bytes(),
+        self_app.share_threshold,
+        self_app.share_total,
+    )?;
+    let folder = match FileDialog::new()
+        .set_title("Save Share Cards")
+        .pick_folder()
+    {
+        Some(folder) => folder,
+        None => {
+            return Err("No folder selected".to_string());
+        }
+    };
+    let date = chrono::Local::now();
+    let date = date.format("%d-%m-%Y-%H-%M").to_string();
+    for share in shares.iter() {
+        let share_text = share.encode();
+        let qr_img = qr::qr_image(share_text.as_str(), 350, self_app.qr_ec_level)
+            .ok_or("Couldn't create share QR code")?;
+        let label = format!("SHARE {} OF {}", share.index, share.total);
+        let (img, _) = card::render_wallet_side(self_app, &qr_img, Some(label.as_str()))
+            .ok_or("Couldn't render share card")?;
+        let bytes = verify::encode_verified(
+            &img,
+            self_app.export_format.as_str(),
+            share_text.as_str(),
+            self_app.address_qr_text.as_str(),
+            card::addr_region(self_app.card_style),
+        )
+        .map_err(|e| format!("Share {} not saved: {}", share.index, e))?;
+        let file = folder.join(format!(
+            "gem-share-{}-of-{}-{}.{}",
+            share.index, share.total, date, self_app.export_format
+        ));
+        std::fs::write(file, bytes).map_err(|e| e.to_string())?;
+    }
+    Ok(format!(
+        "Saved {} share cards, any {} of them restore the wallet",
+        shares.len(),
+        self_app.share_threshold
+    ))
+}
+
+// Recombines pasted shares (one per line) back into the mnemonic and switches to manual mode
+fn recombine_shares(self_app: &mut GemApp) -> Result<String, String> {
+    let shares = self_app
+        .shares_text
+        .lines()
+        .filter(|x| !x.trim().is_empty())
+        .map(shamir::Share::decode)
+        .collect::<Result<Vec<shamir::Share>, String>>()?;
+    let secret = shamir::combine(&shares)?;
+    let mnemonic = String::from_utf8(secret).map_err(|_| "Shares don't contain a mnemonic")?;
+    let words = mnemonic
+        .split(" ")
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>();
+    if words.len() != 25 {
+        return Err("Recombined mnemonic is not 25 words long".to_string());
+    }
+    self_app.auto_wallet = false;
+    self_app.address = derive_address_from_mnemonic(words);
+    self_app.mnemonic = mnemonic;
+    update_qr_codes(self_app);
+    Ok("Wallet recombined from shares".to_string())
 }
 
 impl eframe::App for GemApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         if !self.booted {
+            apply_settings(self, settings::load());
+            load_fonts(self, ctx);
             auto_fill(self, true);
             self.booted = true;
         }
-        // Change background color
-        ctx.set_visuals(egui::Visuals::dark());
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let font = Asset::get("MoneroGothic_v3.otf").unwrap();
-            let font_bytes = font.data.to_vec();
-            let font = Font::try_from_vec(font_bytes).unwrap();
-            // Load image from Asset
-            let card = Asset::get("empty_card.png").unwrap();
-            let img = ImageReader::with_format(
-                card.data.as_bytes(),
-                image::ImageFormat::Png,
-            );
-            let mut img = match image::load_from_memory(img.into_inner()) {
-                Ok(img) => {
-                    let img = img.to_rgba8();
-                    let img = DynamicImage::ImageRgba8(img);
+        // The payment URI follows the amount and message as they are typed
+        if self.payment_uri
+            && !self.deposit_address.is_empty()
+            && address_qr_payload(self) != self.address_qr_wanted
+        {
+            update_address_qr(self);
+        }
+        if let Some(rx) = &self.funding_rx {
+            match rx.try_recv() {
+                Ok(report) => {
+                    self.funding_status = match report {
+                        Ok(report) => report.summary(),
+                        Err(e) => e,
+                    };
+                    self.funding_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint();
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.funding_rx = None;
+                }
+            }
+        }
+        if let Some(rx) = &self.txids_rx {
+            match rx.try_recv() {
+                Ok(verified) => {
+                    self.txids_status = match verified {
+                        Ok(report) => {
+                            self.txids = self.txids_pending.join(",");
+                            self.verified_txids = self.txids.clone();
+                            self.verified_total = report.total();
+                            report.summary()
+                        }
+                        Err(e) => e,
+                    };
+                    self.txids_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint();
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.txids_rx = None;
+                }
+            }
+        }
+        if let Some(rx) = &self.fund_rx {
+            match rx.try_recv() {
+                Ok(transfer) => {
+                    self.fund_status = match transfer {
+                        Ok(transfer) => {
+                            let single = self.fund_pending.len() == 1
+                                && self.fund_pending[0].0 == self.deposit_address;
+                            // A batch may be split over several transactions, which one pays
+                            // which card is left to "Check Funding" on each card
+                            if single {
+                                self.txids = transfer.txids.join(",");
+                            } else {
+                                self.fund_batch.clear();
+                            }
+                            format!(
+                                "Sent {} with {} fee, TXID {}",
+                                funding::format_xmr(transfer.amount),
+                                funding::format_xmr(transfer.fee),
+                                transfer.txids.join(",")
+                            )
+                        }
+                        Err(e) => e,
+                    };
+                    self.fund_pending.clear();
+                    self.fund_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint();
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.fund_pending.clear();
+                    self.fund_rx = None;
+                }
+            }
+        }
+        if let Some(rx) = &self.height_rx {
+            match rx.try_recv() {
+                Ok(height) => {
+                    self.height_status = match height {
+                        Ok((block, height)) => {
+                            self.block_height = height;
+                            update_qr_codes(self);
+                            format!(
+                                "Block {} on {}, minus {} blocks margin",
+                                block,
+                                self.date.format("%d/%m/%Y"),
+                                daemon::DATE_HEIGHT_MARGIN
+                            )
+                        }
+                        Err(e) => e,
+                    };
+                    self.height_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint();
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.height_rx = None;
+                }
+            }
+        }
+        if let Some(rx) = &self.reclaim_rx {
+            match rx.try_recv() {
+                Ok(balance) => {
+                    self.reclaim_checked = balance.is_ok();
+                    self.reclaim_status = match balance {
+                        Ok(balance) => format!(
+                            "Balance {}, {} unlocked",
+                            funding::format_xmr(balance.balance),
+                            funding::format_xmr(balance.unlocked)
+                        ),
+                        Err(e) => e,
+                    };
+                    self.reclaim_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint();
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.reclaim_rx = None;
+                }
+            }
+        }
+        if let Some(rx) = &self.sweep_rx {
+            match rx.try_recv() {
+                Ok(transfer) => {
+                    self.reclaim_status = match transfer {
+                        Ok(transfer) => {
+                            self.reclaim_card = None;
+                            self.reclaim_checked = false;
+                            format!(
+                                "Swept {} with {} fee, TXID {}",
+                                funding::format_xmr(transfer.amount),
+                                funding::format_xmr(transfer.fee),
+                                transfer.txids.join(",")
+                            )
+                        }
+                        Err(e) => e,
+                    };
+                    self.sweep_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint();
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.sweep_rx = None;
+                }
+            }
+        }
+        // Persist settings whenever they change
+        let settings = settings_from_app(self);
+        if settings != self.saved_settings {
+            let _ = settings::save(&settings);
+            self.saved_settings = settings;
+        }
+        let dark = match self.theme {
+            preview::Theme::Dark => true,
+            preview::Theme::Light => false,
+            preview::Theme::System => frame
+                .info()
+                .system_theme
+                .map(|x| x == eframe::Theme::Dark)
+                .unwrap_or(true),
+        };
+        ctx.set_visuals(if dark {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        });
+        // Rendering the card is slow, it is only redone when something on it changed
+        let key = preview_key(self);
+        if key != self.preview_key {
+            // Rendered at full resolution, only the preview is scaled
+            let rendered = if self.card_style == CardStyle::DoubleSided && !self.preview_back {
+                card::render_front(self)
+            } else {
+                card::render_wallet_side(self, &self.qr_main, None)
+            };
+            let img = match rendered {
+                Some((img, warnings)) => {
+                    self.layout_warnings = warnings.join(", ");
                     img
                 }
-                Err(_) => {
+                None => {
                     return ();
                 }
             };
-            let font_size = 20.0;
-            let black = Rgba([0, 0, 0, 0]);
-            draw_text_mut(
-                &mut img,
-                black,
-                160,
-                65,
-                Scale { x: 60.0, y: 60.0 },
-                &font,
-                "MONERO GIFT",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                160,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                format!(
-                    "Congratulations! You have been gifted {} XMR (~{:.2})",
-                    self.amount,
-                    self.value_xmr * self.amount
-                )
-                .as_str(),
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                190,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "You can redeem this gift at any time into a Monero wallet.",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                220,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "For example, you can use the instructions below for",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                250,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "redeeming this gift into the Cake Wallet app:",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                280,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "1 - Install and open the Cake Wallet app on your phone.",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                310,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "2 - Tap the 'Restore Wallet' button.",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                340,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "3 - Tap the 'Scan QR Code' button.",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                370,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "4 - Scan the big QR code on the side.",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                400,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "After importing, you can use the XMR in the wallet as you wish.",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                430,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "Message: ",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                460,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                format!("- {}", self.description).as_str(),
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                490,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "Contact:",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                60,
-                520,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                format!("- {}", self.contact).as_str(),
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                740,
-                30,
-                Scale { x: 30.0, y: 30.0 },
-                &font,
-                "WALLET",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                660,
-                405,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                "ADDRESS",
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                800,
-                440,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                format!("Date: {}", self.date.format("%d/%m/%Y")).as_str(),
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                800,
-                470,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                format!("Height: {}", self.block_height).as_str(),
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                800,
-                500,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                format!("From {}", self.from).as_str(),
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                800,
-                500,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                format!("From {}", self.from).as_str(),
-            );
-            draw_text_mut(
-                &mut img,
-                black,
-                800,
-                530,
-                Scale {
-                    x: font_size,
-                    y: font_size,
-                },
-                &font,
-                format!("To {}", self.to).as_str(),
-            );
-            draw_line_segment_mut(&mut img, (575.0, 0.0), (575.0, 590.0), black);
-            ui.vertical_centered(|ui| {
-                // Grid with width of entire ui
-                egui::Grid::new("my_grid")
-                    .striped(true)
-                    .num_columns(4)
-                    .min_col_width(1000.0 / 4.0)
-                    .max_col_width(1000.0 / 4.0)
-                    .show(ui, |ui| {
-                        // First row
-                        // label with big font
-                        ui.heading("Gift Amount: ");
-                        ui.add(
-                            egui::DragValue::new(&mut self.amount)
-                                .speed(0.01)
-                                .fixed_decimals(4)
-                                .clamp_range(0.0..=1000000.0),
-                        );
-                        ui.heading("Auto Fill (Might Be Slow): ");
-                        if ui.checkbox(&mut self.auto_wallet, "").clicked() && self.auto_wallet {
-                            auto_fill(self, false);
-                        }
-                        ui.end_row();
-                        ui.heading("Mnemonic: ");
-                        if self.auto_wallet {
-                            ui.horizontal(|ui| {
+            self.img = img.clone();
+            // Only the preview is greyed, exports keep their colours
+            let shown = if self.preview_gray {
+                preview::grayscale(&img)
+            } else {
+                img
+            };
+            let color_image = match &shown {
+                DynamicImage::ImageRgb8(image) => {
+                    // common case optimization
+                    egui::ColorImage::from_rgb(
+                        [image.width() as usize, image.height() as usize],
+                        image.as_bytes(),
+                    )
+                }
+                other => {
+                    let image = other.to_rgba8();
+                    egui::ColorImage::from_rgba_unmultiplied(
+                        [image.width() as usize, image.height() as usize],
+                        image.as_bytes(),
+                    )
+                }
+            };
+            let size = egui::vec2(color_image.size[0] as f32, color_image.size[1] as f32);
+            // you must keep the handle, if the handle is destroyed so the texture will be destroyed as well
+            let handle = ctx.load_texture("gem", color_image, egui::TextureOptions::default());
+            self.preview = Some((handle, size));
+            self.preview_key = key;
+        }
+        // The controls scroll in a panel that can be resized, the preview gets the rest
+        egui::TopBottomPanel::top("controls")
+            .resizable(true)
+            .default_height(380.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        // Grid with width of entire ui
+                        let column_width = ((ui.available_width() - 30.0) / 4.0).max(200.0);
+                        egui::Grid::new("my_grid")
+                            .striped(true)
+                            .num_columns(4)
+                            .min_col_width(column_width)
+                            .max_col_width(column_width)
+                            .show(ui, |ui| {
+                                // First row
+                                // label with big font
+                                ui.heading("Gift Amount: ");
                                 ui.add(
-                                    egui::TextEdit::singleline(&mut self.mnemonic)
-                                        .interactive(false),
+                                    egui::DragValue::new(&mut self.amount)
+                                        .speed(0.01)
+                                        .fixed_decimals(4)
+                                        .clamp_range(0.0..=1000000.0),
                                 );
-                                if ui.button("Copy").clicked() {
-                                    let mut ctx: clipboard::ClipboardContext =
-                                        clipboard::ClipboardProvider::new().unwrap();
-                                    ctx.set_contents(self.mnemonic.clone()).unwrap();
+                                ui.heading("Auto Fill (Might Be Slow): ");
+                                if ui.checkbox(&mut self.auto_wallet, "").clicked()
+                                    && self.auto_wallet
+                                {
+                                    auto_fill(self, false);
                                 }
-                            });
-                        } else {
-                            ui.horizontal(|ui| {
-                                ui.add(egui::TextEdit::singleline(&mut self.mnemonic));
-                                if ui.button("Copy").clicked() {
-                                    let mut ctx: clipboard::ClipboardContext =
-                                        clipboard::ClipboardProvider::new().unwrap();
-                                    ctx.set_contents(self.mnemonic.clone()).unwrap();
+                                ui.end_row();
+                                ui.heading("Mnemonic: ");
+                                if self.auto_wallet {
+                                    ui.horizontal(|ui| {
+                                        ui.add(
+                                            egui::TextEdit::singleline(&mut self.mnemonic)
+                                                .interactive(false),
+                                        );
+                                        if ui.button("Copy").clicked() {
+                                            let mut ctx: clipboard::ClipboardContext =
+                                                clipboard::ClipboardProvider::new().unwrap();
+                                            ctx.set_contents(self.mnemonic.clone()).unwrap();
+                                        }
+                                    });
+                                } else {
+                                    ui.horizontal(|ui| {
+                                        ui.add(egui::TextEdit::singleline(&mut self.mnemonic));
+                                        if ui.button("Copy").clicked() {
+                                            let mut ctx: clipboard::ClipboardContext =
+                                                clipboard::ClipboardProvider::new().unwrap();
+                                            ctx.set_contents(self.mnemonic.clone()).unwrap();
+                                        }
+                                    });
                                 }
-                            });
-                        }
-                        ui.heading("Address: ");
-                        if self.auto_wallet {
-                            ui.horizontal(|ui| {
+                                ui.heading("Address: ");
+                                if self.auto_wallet {
+                                    ui.horizontal(|ui| {
+                                        ui.add(
+                                            egui::TextEdit::singleline(&mut self.address)
+                                                .interactive(false),
+                                        );
+                                        if ui.button("Copy").clicked() {
+                                            let mut ctx: clipboard::ClipboardContext =
+                                                clipboard::ClipboardProvider::new().unwrap();
+                                            ctx.set_contents(self.address.clone()).unwrap();
+                                        }
+                                    });
+                                } else {
+                                    ui.horizontal(|ui| {
+                                        ui.add(egui::TextEdit::singleline(&mut self.address));
+                                        if ui.button("Copy").clicked() {
+                                            let mut ctx: clipboard::ClipboardContext =
+                                                clipboard::ClipboardProvider::new().unwrap();
+                                            ctx.set_contents(self.address.clone()).unwrap();
+                                        }
+                                    });
+                                }
+                                ui.end_row();
+                                if self.auto_wallet {
+                                    ui.heading("Block Height (Current - 1k): ");
+                                    ui.label(self.block_height.to_string());
+                                } else {
+                                    ui.heading("Block Height: ");
+                                    ui.add(
+                                        egui::DragValue::new(&mut self.block_height)
+                                            .speed(100)
+                                            .fixed_decimals(0)
+                                            .clamp_range(0.0..=100000000.0),
+                                    );
+                                }
+                                ui.heading("Date: ");
+                                if self.auto_wallet {
+                                    ui.label(self.date.format("%Y-%m-%d").to_string());
+                                } else {
+                                    ui.horizontal(|ui| {
+                                        ui.add(egui_extras::DatePickerButton::new(&mut self.date));
+                                        if ui.button("Height From Date").clicked() {
+                                            start_height_lookup(self);
+                                        }
+                                        ui.label(self.height_status.as_str());
+                                    });
+                                }
+                                ui.end_row();
+                                ui.heading("Value Per XMR: ");
+                                if self.auto_wallet {
+                                    ui.label(format!(
+                                        "{:.2} {}",
+                                        self.value_xmr,
+                                        self.currency.to_uppercase()
+                                    ));
+                                } else {
+                                    ui.add(
+                                        egui::DragValue::new(&mut self.value_xmr)
+                                            .speed(0.01)
+                                            .fixed_decimals(2)
+                                            .clamp_range(0.0..=1000000.0),
+                                    );
+                                }
+                                ui.heading("Message: ");
                                 ui.add(
-                                    egui::TextEdit::singleline(&mut self.address)
-                                        .interactive(false),
+                                    egui::TextEdit::singleline(&mut self.description)
+                                        .char_limit(200),
                                 );
-                                if ui.button("Copy").clicked() {
-                                    let mut ctx: clipboard::ClipboardContext =
-                                        clipboard::ClipboardProvider::new().unwrap();
-                                    ctx.set_contents(self.address.clone()).unwrap();
-                                }
+                                ui.end_row();
+                                ui.heading("From - To: ");
+                                ui.horizontal(|ui| {
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut self.from)
+                                            .char_limit(40)
+                                            .desired_width(130.0),
+                                    );
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut self.to)
+                                            .char_limit(40)
+                                            .desired_width(150.0),
+                                    );
+                                });
+                                ui.heading("Contact: ");
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut self.contact).char_limit(120),
+                                );
+                                ui.end_row();
                             });
-                        } else {
-                            ui.horizontal(|ui| {
-                                ui.add(egui::TextEdit::singleline(&mut self.address));
-                                if ui.button("Copy").clicked() {
-                                    let mut ctx: clipboard::ClipboardContext =
-                                        clipboard::ClipboardProvider::new().unwrap();
-                                    ctx.set_contents(self.address.clone()).unwrap();
+                        egui::Grid::new("my_grid_2")
+                            .striped(true)
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                ui.heading("TXIDs (Seperate with comma): ");
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .add_enabled(
+                                            self.txids_rx.is_none(),
+                                            egui::Button::new("Verify"),
+                                        )
+                                        .clicked()
+                                    {
+                                        start_txids_check(self);
+                                    }
+                                    ui.label(self.txids_status.as_str());
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut self.txids)
+                                            .desired_width(f32::INFINITY)
+                                            .char_limit(330),
+                                    );
+                                });
+                                ui.end_row();
+                                if self.auto_wallet {
+                                    ui.heading("Extra Entropy (Dice 1-6 or Coins H/T): ");
+                                    ui.horizontal(|ui| {
+                                        ui.add(egui::TextEdit::singleline(&mut self.user_entropy));
+                                        if self.user_entropy.trim().is_empty() {
+                                            ui.label("Not used");
+                                        } else {
+                                            match entropy::parse_user_entropy(
+                                                self.user_entropy.as_str(),
+                                            ) {
+                                                Ok(user) => {
+                                                    ui.label(format!("~{:.1} bits", user.bits))
+                                                }
+                                                Err(e) => ui.label(e),
+                                            };
+                                        }
+                                        ui.label(self.entropy_status.as_str());
+                                    });
+                                    ui.end_row();
                                 }
+                                ui.heading("QR Error Correction: ");
+                                ui.horizontal(|ui| {
+                                    let ec_level = self.qr_ec_level;
+                                    egui::ComboBox::from_id_source("qr_ec_level")
+                                        .selected_text(choice::name(
+                                            &qr::EC_LEVELS,
+                                            self.qr_ec_level,
+                                        ))
+                                        .show_ui(ui, |ui| {
+                                            for (level, name) in qr::EC_LEVELS {
+                                                ui.selectable_value(
+                                                    &mut self.qr_ec_level,
+                                                    level,
+                                                    name,
+                                                );
+                                            }
+                                        });
+                                    if ec_level != self.qr_ec_level {
+                                        update_qr_codes(self);
+                                    }
+                                });
+                                ui.end_row();
+                                ui.heading("Card Layout: ");
+                                ui.horizontal(|ui| {
+                                    egui::ComboBox::from_id_source("card_style")
+                                        .selected_text(choice::name(
+                                            &card::CARD_STYLES,
+                                            self.card_style,
+                                        ))
+                                        .show_ui(ui, |ui| {
+                                            for (style, name) in card::CARD_STYLES {
+                                                ui.selectable_value(
+                                                    &mut self.card_style,
+                                                    style,
+                                                    name,
+                                                );
+                                            }
+                                        });
+                                    if self.card_style == CardStyle::DoubleSided {
+                                        egui::ComboBox::from_id_source("duplex_flip")
+                                            .selected_text(choice::name(
+                                                &duplex::FLIPS,
+                                                self.duplex_flip,
+                                            ))
+                                            .show_ui(ui, |ui| {
+                                                for (flip, name) in duplex::FLIPS {
+                                                    ui.selectable_value(
+                                                        &mut self.duplex_flip,
+                                                        flip,
+                                                        name,
+                                                    );
+                                                }
+                                            });
+                                        ui.label("Preview");
+                                        ui.radio_value(&mut self.preview_back, false, "Front");
+                                        ui.radio_value(&mut self.preview_back, true, "Back");
+                                    }
+                                });
+                                ui.end_row();
+                                ui.heading("Redemption Guide: ");
+                                ui.horizontal(|ui| {
+                                    let guide = self.guide;
+                                    egui::ComboBox::from_id_source("guide")
+                                        .selected_text(choice::name(&guide::GUIDES, self.guide))
+                                        .show_ui(ui, |ui| {
+                                            for (guide, name) in guide::GUIDES {
+                                                ui.selectable_value(&mut self.guide, guide, name);
+                                            }
+                                        });
+                                    let secret = self.secret;
+                                    ui.label("Card Holds");
+                                    egui::ComboBox::from_id_source("secret")
+                                        .selected_text(choice::name(&guide::SECRETS, self.secret))
+                                        .show_ui(ui, |ui| {
+                                            for (secret, name) in guide::SECRETS {
+                                                ui.selectable_value(&mut self.secret, secret, name);
+                                            }
+                                        });
+                                    if guide != self.guide || secret != self.secret {
+                                        update_qr_codes(self);
+                                    }
+                                });
+                                ui.end_row();
+                                ui.heading("Help Link: ");
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .add(
+                                            egui::TextEdit::singleline(&mut self.help_url)
+                                                .hint_text(
+                                                    "https://... or file://..., empty for none",
+                                                )
+                                                .desired_width(300.0),
+                                        )
+                                        .changed()
+                                    {
+                                        update_help_qr(self);
+                                    }
+                                    if ui.button("Export Help Page").clicked() {
+                                        self.help_status = match export_help_page(self) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                    ui.label(self.help_status.as_str());
+                                });
+                                ui.end_row();
+                                ui.heading("Deposit Address: ");
+                                ui.horizontal(|ui| {
+                                    let deposit_kind = self.deposit_kind;
+                                    egui::ComboBox::from_id_source("deposit_kind")
+                                        .selected_text(choice::name(
+                                            &address::DEPOSIT_KINDS,
+                                            self.deposit_kind,
+                                        ))
+                                        .show_ui(ui, |ui| {
+                                            for (kind, name) in address::DEPOSIT_KINDS {
+                                                ui.selectable_value(
+                                                    &mut self.deposit_kind,
+                                                    kind,
+                                                    name,
+                                                );
+                                            }
+                                        });
+                                    match self.deposit_kind {
+                                        DepositKind::Primary => {}
+                                        DepositKind::Subaddress => {
+                                            ui.label("Account");
+                                            ui.add(egui::DragValue::new(&mut self.sub_account));
+                                            ui.label("Index");
+                                            ui.add(egui::DragValue::new(&mut self.sub_index));
+                                        }
+                                        DepositKind::Integrated => {
+                                            ui.label("Payment ID");
+                                            ui.add(
+                                                egui::TextEdit::singleline(&mut self.payment_id)
+                                                    .char_limit(16)
+                                                    .desired_width(150.0),
+                                            );
+                                            if ui.button("Random").clicked() {
+                                                self.payment_id = address::random_payment_id();
+                                            }
+                                        }
+                                    }
+                                    if self.deposit_kind == DepositKind::Integrated
+                                        && self.payment_id.is_empty()
+                                    {
+                                        self.payment_id = address::random_payment_id();
+                                    }
+                                    if deposit_kind != self.deposit_kind
+                                        || ui.button("Apply").clicked()
+                                    {
+                                        update_qr_codes(self);
+                                    }
+                                    if ui.button("Copy").clicked() {
+                                        let mut ctx: clipboard::ClipboardContext =
+                                            clipboard::ClipboardProvider::new().unwrap();
+                                        ctx.set_contents(self.deposit_address.clone()).unwrap();
+                                    }
+                                    if ui
+                                        .checkbox(&mut self.payment_uri, "Payment URI QR")
+                                        .on_hover_text(
+                                            "monero: URI with the gift amount and message",
+                                        )
+                                        .changed()
+                                    {
+                                        update_address_qr(self);
+                                    }
+                                });
+                                ui.end_row();
+                                ui.heading("Project: ");
+                                ui.horizontal(|ui| {
+                                    if ui.button("Open Project").clicked() {
+                                        self.project_status = match open_project(self) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                    if ui.button("Save Project").clicked() {
+                                        self.project_status = match save_project(self) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                    ui.checkbox(
+                                        &mut self.project_include_wallet,
+                                        "Include wallet and mnemonic (secret!)",
+                                    );
+                                    if ui.button("Import Wallet").clicked() {
+                                        self.project_status = match import_wallet(self) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                    ui.label(self.project_status.as_str());
+                                });
+                                ui.end_row();
+                                ui.heading("Settings: ");
+                                ui.horizontal(|ui| {
+                                    ui.label("Proxy");
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut self.proxy)
+                                            .hint_text("socks5://127.0.0.1:9050")
+                                            .desired_width(180.0),
+                                    );
+                                    ui.label("Currency");
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut self.currency)
+                                            .char_limit(5)
+                                            .desired_width(40.0),
+                                    );
+                                    ui.label("Seed Language");
+                                    egui::ComboBox::from_id_source("language")
+                                        .selected_text(
+                                            LANGUAGES
+                                                .iter()
+                                                .find(|x| x.0 == self.language)
+                                                .map(|x| x.1)
+                                                .unwrap_or(""),
+                                        )
+                                        .show_ui(ui, |ui| {
+                                            for (code, name) in LANGUAGES {
+                                                ui.selectable_value(
+                                                    &mut self.language,
+                                                    code.to_string(),
+                                                    name,
+                                                );
+                                            }
+                                        });
+                                    ui.label("Export");
+                                    egui::ComboBox::from_id_source("export_format")
+                                        .selected_text(self.export_format.to_uppercase())
+                                        .show_ui(ui, |ui| {
+                                            for format in ["jpg", "png"] {
+                                                ui.selectable_value(
+                                                    &mut self.export_format,
+                                                    format.to_string(),
+                                                    format.to_uppercase(),
+                                                );
+                                            }
+                                        });
+                                });
+                                ui.end_row();
+                                ui.heading("Display: ");
+                                ui.horizontal(|ui| {
+                                    egui::ComboBox::from_id_source("theme")
+                                        .selected_text(choice::name(&preview::THEMES, self.theme))
+                                        .show_ui(ui, |ui| {
+                                            for (theme, name) in preview::THEMES {
+                                                ui.selectable_value(&mut self.theme, theme, name);
+                                            }
+                                        });
+                                    ui.checkbox(&mut self.print_preview, "Print Preview");
+                                    if self.print_preview {
+                                        egui::ComboBox::from_id_source("paper")
+                                            .selected_text(choice::name(
+                                                &preview::PAPERS,
+                                                self.paper,
+                                            ))
+                                            .show_ui(ui, |ui| {
+                                                for (paper, name) in preview::PAPERS {
+                                                    ui.selectable_value(
+                                                        &mut self.paper,
+                                                        paper,
+                                                        name,
+                                                    );
+                                                }
+                                            });
+                                        ui.label("Screen DPI");
+                                        ui.add(
+                                            egui::DragValue::new(&mut self.screen_dpi)
+                                                .speed(1)
+                                                .fixed_decimals(0)
+                                                .clamp_range(50.0..=600.0),
+                                        );
+                                    }
+                                    ui.checkbox(&mut self.preview_gray, "Grayscale");
+                                });
+                                ui.end_row();
+                                ui.heading("Fallback Font: ");
+                                ui.horizontal(|ui| {
+                                    ui.label(if self.fallback_font.is_empty() {
+                                        "System fonts only"
+                                    } else {
+                                        self.fallback_font.as_str()
+                                    });
+                                    if ui.button("Pick Font").clicked() {
+                                        self.font_status = match pick_fallback_font(self, ctx) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                    if !self.fallback_font.is_empty()
+                                        && ui.button("Clear").clicked()
+                                    {
+                                        self.fallback_font = "".to_string();
+                                        load_fonts(self, ctx);
+                                        self.font_status = "".to_string();
+                                    }
+                                    ui.label(self.font_status.as_str());
+                                });
+                                ui.end_row();
+                                ui.heading("Node (Daemon RPC): ");
+                                ui.horizontal(|ui| {
+                                    ui.add(egui::TextEdit::singleline(&mut self.node_url));
+                                    if ui
+                                        .add_enabled(
+                                            self.funding_rx.is_none(),
+                                            egui::Button::new("Check Funding"),
+                                        )
+                                        .clicked()
+                                    {
+                                        start_funding_check(self);
+                                    }
+                                    ui.label(self.funding_status.as_str());
+                                });
+                                ui.end_row();
+                                ui.heading("Wallet RPC (Hot Wallet): ");
+                                ui.horizontal(|ui| {
+                                    ui.add(egui::TextEdit::singleline(&mut self.wallet_rpc_url));
+                                    if self.fund_confirm {
+                                        // Sending money needs a second click
+                                        if ui
+                                            .button(format!(
+                                                "Confirm Sending {:.4} XMR",
+                                                self.amount
+                                            ))
+                                            .clicked()
+                                        {
+                                            self.fund_status = match fund_card(self) {
+                                                Ok(status) => status,
+                                                Err(e) => e,
+                                            };
+                                            self.fund_confirm = false;
+                                        }
+                                        if ui.button("Cancel").clicked() {
+                                            self.fund_confirm = false;
+                                        }
+                                    } else if ui
+                                        .add_enabled(
+                                            self.fund_rx.is_none(),
+                                            egui::Button::new("Fund Card"),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.fund_confirm = true;
+                                    }
+                                    ui.label(self.fund_status.as_str());
+                                });
+                                ui.end_row();
+                                ui.heading("Fund Batch: ");
+                                ui.horizontal(|ui| {
+                                    if ui.button("Add Card To Batch").clicked() {
+                                        self.fund_status = match add_to_fund_batch(self) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                    let total: u64 = self.fund_batch.iter().map(|x| x.1).sum();
+                                    if self.fund_batch_confirm {
+                                        if ui
+                                            .button(format!(
+                                                "Confirm Sending {} To {} Cards",
+                                                funding::format_xmr(total),
+                                                self.fund_batch.len()
+                                            ))
+                                            .clicked()
+                                        {
+                                            self.fund_status = match fund_batch(self) {
+                                                Ok(status) => status,
+                                                Err(e) => e,
+                                            };
+                                            self.fund_batch_confirm = false;
+                                        }
+                                        if ui.button("Cancel").clicked() {
+                                            self.fund_batch_confirm = false;
+                                        }
+                                    } else if ui
+                                        .add_enabled(
+                                            !self.fund_batch.is_empty() && self.fund_rx.is_none(),
+                                            egui::Button::new(format!(
+                                                "Fund {} Cards",
+                                                self.fund_batch.len()
+                                            )),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.fund_batch_confirm = true;
+                                    }
+                                    if ui
+                                        .add_enabled(
+                                            !self.fund_batch.is_empty() && self.fund_rx.is_none(),
+                                            egui::Button::new("Clear"),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.fund_batch.clear();
+                                    }
+                                });
+                                ui.end_row();
+                                ui.heading("Watch Only (For You): ");
+                                ui.horizontal(|ui| {
+                                    if ui.button("Save Watch Card").clicked() {
+                                        self.watch_status = match save_watch_card(self) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                    ui.label(self.watch_status.as_str());
+                                });
+                                ui.end_row();
+                                ui.heading("Reclaim Unredeemed Card: ");
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .add_enabled(
+                                            self.sweep_rx.is_none(),
+                                            egui::Button::new("Load Card"),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.reclaim_status = match load_reclaim_card(self) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                    if ui
+                                        .add_enabled(
+                                            self.reclaim_card.is_some()
+                                                && self.reclaim_rx.is_none()
+                                                && self.sweep_rx.is_none(),
+                                            egui::Button::new("Check Balance"),
+                                        )
+                                        .clicked()
+                                    {
+                                        start_reclaim_balance(self);
+                                    }
+                                    ui.label("Sweep To");
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut self.reclaim_to)
+                                            .desired_width(200.0),
+                                    );
+                                    if self.reclaim_confirm {
+                                        // Sweeping moves the whole gift, it needs a second click
+                                        if ui.button("Confirm Sweep").clicked() {
+                                            start_reclaim_sweep(self);
+                                            self.reclaim_confirm = false;
+                                        }
+                                        if ui.button("Cancel").clicked() {
+                                            self.reclaim_confirm = false;
+                                        }
+                                    } else if ui
+                                        .add_enabled(
+                                            self.reclaim_card.is_some()
+                                                && self.reclaim_checked
+                                                && self.reclaim_rx.is_none()
+                                                && self.sweep_rx.is_none(),
+                                            egui::Button::new("Sweep"),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.reclaim_confirm = true;
+                                    }
+                                    ui.label(self.reclaim_status.as_str());
+                                });
+                                ui.end_row();
+                                ui.heading("Shares (Needed of Total): ");
+                                ui.horizontal(|ui| {
+                                    ui.add(
+                                        egui::DragValue::new(&mut self.share_threshold)
+                                            .clamp_range(2..=self.share_total),
+                                    );
+                                    ui.label("of");
+                                    ui.add(
+                                        egui::DragValue::new(&mut self.share_total)
+                                            .clamp_range(2..=16),
+                                    );
+                                    if ui.button("Save Share Cards").clicked() {
+                                        self.share_status = match save_share_cards(self) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                    ui.label(self.share_status.as_str());
+                                });
+                                ui.end_row();
+                                ui.heading("Recombine Shares (One per line): ");
+                                ui.horizontal(|ui| {
+                                    ui.add(
+                                        egui::TextEdit::multiline(&mut self.shares_text)
+                                            .desired_rows(2),
+                                    );
+                                    if ui.button("Recombine").clicked() {
+                                        self.share_status = match recombine_shares(self) {
+                                            Ok(status) => status,
+                                            Err(e) => e,
+                                        };
+                                    }
+                                });
+                                ui.end_row();
                             });
-                        }
-                        ui.end_row();
-                        if self.auto_wallet {
-                            ui.heading("Block Height (Current - 1k): ");
-                            ui.label(self.block_height.to_string());
-                        } else {
-                            ui.heading("Block Height: ");
-                            ui.add(
-                                egui::DragValue::new(&mut self.block_height)
-                                    .speed(100)
-                                    .fixed_decimals(0)
-                                    .clamp_range(0.0..=100000000.0),
-                            );
-                        }
-                        ui.heading("Date: ");
-                        if self.auto_wallet {
-                            ui.label(self.date.format("%Y-%m-%d").to_string());
-                        } else {
-                            ui.add(egui_extras::DatePickerButton::new(&mut self.date));
-                        }
-                        ui.end_row();
-                        ui.heading("Value Per XMR: ");
-                        if self.auto_wallet {
-                            ui.label(format!("${:.2}", self.value_xmr));
-                        } else {
-                            ui.add(
-                                egui::DragValue::new(&mut self.value_xmr)
-                                    .speed(0.01)
-                                    .fixed_decimals(2)
-                                    .clamp_range(0.0..=1000000.0),
-                            );
-                        }
-                        ui.heading("Message: ");
-                        ui.add(egui::TextEdit::singleline(&mut self.description).char_limit(60));
-                        ui.end_row();
-                        ui.heading("From - To: ");
+                        ui.add_space(10.0);
                         ui.horizontal(|ui| {
-                            ui.add(
-                                egui::TextEdit::singleline(&mut self.from)
-                                    .char_limit(15)
-                                    .desired_width(130.0),
-                            );
-                            ui.add(
-                                egui::TextEdit::singleline(&mut self.to)
-                                    .char_limit(18)
-                                    .desired_width(150.0),
-                            );
+                            ui.add_space(ui.available_width() * 0.36);
+                            if self.auto_wallet {
+                                if ui.button("Generate New Wallet").clicked() {
+                                    generate_wallet(self);
+                                }
+                            } else {
+                                if ui.button("Update QR Codes").clicked() {
+                                    update_qr_codes(self);
+                                }
+                            }
+                            if ui.button("Save This Image").clicked() {
+                                self.save_status = match save_card(self) {
+                                    Ok(status) => status,
+                                    Err(e) => e,
+                                };
+                            }
+                            ui.label(self.save_status.as_str());
+                            if !self.layout_warnings.is_empty() {
+                                ui.colored_label(
+                                    egui::Color32::YELLOW,
+                                    self.layout_warnings.as_str(),
+                                );
+                            }
                         });
-                        ui.heading("Contact: ");
-                        ui.add(egui::TextEdit::singleline(&mut self.contact).char_limit(60));
-                        ui.end_row();
                     });
-                egui::Grid::new("my_grid_2")
-                    .striped(true)
-                    .num_columns(2)
-                    .show(ui, |ui| {
-                        ui.heading("TXIDs (Seperate with comma): ");
-                        ui.add(egui::TextEdit::singleline(&mut self.txids).desired_width(f32::INFINITY).char_limit(120));
-                        ui.end_row();
-                    });
-                ui.add_space(10.0);
-                ui.horizontal(|ui| {
-                    ui.add_space(400.0);
-                    if self.auto_wallet {
-                        if ui.button("Generate New Wallet").clicked() {
-                            // We have to do all deriving manually for now, libmonero will support generating directly a wallet soon
-                            let mnemonic = generate_seed("en", "original");
-                            let priv_keys = derive_priv_keys(derive_hex_seed(mnemonic.clone()));
-                            let priv_sk = priv_keys[0].to_string();
-                            let priv_vk = priv_keys[1].to_string();
-                            let pub_sk = derive_pub_key(priv_sk);
-                            let pub_vk = derive_pub_key(priv_vk);
-                            let address = libmonero::keys::derive_address(pub_sk, pub_vk, 0);
-                            self.address = address.clone();
-                            self.mnemonic = mnemonic.join(" ");
-                            let mne_str_encoded = mnemonic.join("%20");
-                            let qr_code = QrCode::new(format!(
-                                "monero_wallet:{}?seed={}&height={}",
-                                address, mne_str_encoded, self.block_height
-                            ))
-                            .unwrap();
-                            let qr_img = qr_code.render::<Luma<u8>>().build();
-                            let qr_img = DynamicImage::ImageLuma8(qr_img);
-                            let qr_img =
-                                qr_img.resize_exact(350, 350, image::imageops::FilterType::Nearest);
-                            self.qr_main = qr_img;
-                            let qr_addr_code = QrCode::new(format!("{}", address)).unwrap();
-                            let qr_addr_img = qr_addr_code.render::<Luma<u8>>().build();
-                            let qr_addr_img = DynamicImage::ImageLuma8(qr_addr_img);
-                            let qr_addr_img = qr_addr_img.resize_exact(
-                                150,
-                                150,
-                                image::imageops::FilterType::Nearest,
-                            );
-                            self.qr_addr = qr_addr_img;
-                        }
-                    } else {
-                        if ui.button("Update QR Codes").clicked() {
-                            let mne_str_encoded = (self.mnemonic.split(" "))
-                                .map(|x| x.to_string())
-                                .collect::<Vec<String>>()
-                                .join("%20");
-                            let qr_code = QrCode::new(format!(
-                                "monero_wallet:{}?seed={}&height={}&txids={}",
-                                self.address, mne_str_encoded, self.block_height, self.txids.replace(" ", "")
-                            ))
-                            .unwrap();
-                            let qr_img = qr_code.render::<Luma<u8>>().build();
-                            let qr_img = DynamicImage::ImageLuma8(qr_img);
-                            let qr_img =
-                                qr_img.resize_exact(350, 350, image::imageops::FilterType::Nearest);
-                            self.qr_main = qr_img;
-                            let qr_addr_code = QrCode::new(format!("{}", self.address)).unwrap();
-                            let qr_addr_img = qr_addr_code.render::<Luma<u8>>().build();
-                            let qr_addr_img = DynamicImage::ImageLuma8(qr_addr_img);
-                            let qr_addr_img = qr_addr_img.resize_exact(
-                                150,
-                                150,
-                                image::imageops::FilterType::Nearest,
-                            );
-                            self.qr_addr = qr_addr_img;
-                        }
-                    }
-                    if ui.button("Save This Image").clicked() {
-                        let date = chrono::Local::now();
-                        let date = date.format("%d-%m-%Y-%H-%M").to_string();
-                        let files = FileDialog::new()
-                            .add_filter("jpg", &["jpg"])
-                            .set_title("Save Image")
-                            .set_file_name(format!("gem-wallet-{}.jpg", date))
-                            .save_file();
-                        if let Some(file) = files {
-                            self.img.save(file).unwrap();
-                        }
-                    }
                 });
-                ui.add_space(10.0);
-                // draw qr code
-                for (x, y, pixel) in self.qr_main.pixels() {
-                    let pixel = pixel.0[0];
-                    let pixel = Rgba([pixel, pixel, pixel, 255]);
-                    img.put_pixel(x + 615, y + 55, pixel);
-                }
-                // draw qr code
-                for (x, y, pixel) in self.qr_addr.pixels() {
-                    let pixel = pixel.0[0];
-                    let pixel = Rgba([pixel, pixel, pixel, 255]);
-                    img.put_pixel(x + 620, y + 425, pixel);
-                }
-                self.img = img.clone();
-                let color_image = match &img {
-                    DynamicImage::ImageRgb8(image) => {
-                        // common case optimization
-                        egui::ColorImage::from_rgb(
-                            [image.width() as usize, image.height() as usize],
-                            image.as_bytes(),
-                        )
+            });
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                let (handle, size) = match &self.preview {
+                    Some((handle, size)) => (handle.clone(), *size),
+                    None => {
+                        return ();
                     }
-                    other => {
-                        let image = other.to_rgba8();
-                        egui::ColorImage::from_rgba_unmultiplied(
-                            [image.width() as usize, image.height() as usize],
-                            image.as_bytes(),
+                };
+                ui.horizontal(|ui| {
+                    ui.label("Zoom");
+                    // Print preview is always at physical size
+                    if ui
+                        .add_enabled(
+                            !self.print_preview,
+                            egui::Slider::new(&mut self.zoom, 0.1..=2.0).fixed_decimals(2),
                         )
+                        .changed()
+                    {
+                        self.zoom_fit = false;
                     }
+                    ui.add_enabled(
+                        !self.print_preview,
+                        egui::Checkbox::new(&mut self.zoom_fit, "Fit"),
+                    );
+                });
+                if self.zoom_fit {
+                    let available = ui.available_size();
+                    self.zoom = (available.x / size.x)
+                        .min(available.y / size.y)
+                        .clamp(0.1, 2.0);
+                }
+                // Printed size: the card is rendered at CARD_DPI, the screen has screen_dpi
+                let per_mm = preview::points_per_mm(self.screen_dpi, ctx.pixels_per_point());
+                let scale = if self.print_preview {
+                    per_mm * 25.4 / preview::CARD_DPI
+                } else {
+                    self.zoom
                 };
-                // you must keep the handle, if the handle is destroyed so the texture will be destroyed as well
-                let handle =
-                    ctx.load_texture("gem", color_image.clone(), egui::TextureOptions::default());
-                let sized_image = egui::load::SizedTexture::new(
-                    handle.id(),
-                    egui::vec2(
-                        (color_image.size[0] as f32).div(1.25),
-                        (color_image.size[1] as f32).div(1.25),
-                    ),
-                );
-                let image = egui::Image::from_texture(sized_image);
-                ui.add(image);
+                let sized_image = egui::load::SizedTexture::new(handle.id(), size * scale);
+                // Drag the preview to pan around when it is bigger than the window
+                egui::ScrollArea::both()
+                    .drag_to_scroll(true)
+                    .show(ui, |ui| match preview::paper_size_mm(self.paper) {
+                        Some((width, height)) if self.print_preview => {
+                            let (sheet, _) = ui.allocate_exact_size(
+                                egui::vec2(width * per_mm, height * per_mm),
+                                egui::Sense::hover(),
+                            );
+                            ui.painter().rect_filled(sheet, 0.0, egui::Color32::WHITE);
+                            let margin = preview::PAPER_MARGIN_MM * per_mm;
+                            egui::Image::from_texture(sized_image).paint_at(
+                                ui,
+                                egui::Rect::from_min_size(
+                                    sheet.min + egui::vec2(margin, margin),
+                                    sized_image.size,
+                                ),
+                            );
+                        }
+                        _ => {
+                            ui.add(egui::Image::from_texture(sized_image));
+                        }
+                    });
             });
         });
     }
@@ -0,0 +1,54 @@
+// How the app and the card preview look on screen: the UI theme and the print preview, which
+// shows the card at its printed size on a sheet of paper, optionally in shades of grey.
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Theme {
+    Dark,
+    Light,
+    System,
+}
+
+pub const THEMES: [(Theme, &str); 3] = [
+    (Theme::Dark, "Dark"),
+    (Theme::Light, "Light"),
+    (Theme::System, "Follow System"),
+];
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Paper {
+    A4,
+    Letter,
+    CardOnly,
+}
+
+pub const PAPERS: [(Paper, &str); 3] = [
+    (Paper::A4, "A4"),
+    (Paper::Letter, "US Letter"),
+    (Paper::CardOnly, "Card Only"),
+];
+
+// Card images are rendered at 300 DPI
+pub const CARD_DPI: f32 = 300.0;
+// Same margin as the duplex sheets, 15mm
+pub const PAPER_MARGIN_MM: f32 = 15.0;
+
+// Portrait width and height in millimetres, None when only the card is shown
+pub fn paper_size_mm(paper: Paper) -> Option<(f32, f32)> {
+    match paper {
+        Paper::A4 => Some((210.0, 297.0)),
+        Paper::Letter => Some((215.9, 279.4)),
+        Paper::CardOnly => None,
+    }
+}
+
+// UI points per millimetre so things show at their real size on a screen with `screen_dpi`
+pub fn points_per_mm(screen_dpi: f32, pixels_per_point: f32) -> f32 {
+    screen_dpi / 25.4 / pixels_per_point
+}
+
+// Luma conversion, roughly what a black and white printer makes of the colours
+pub fn grayscale(img: &DynamicImage) -> DynamicImage {
+    DynamicImage::ImageLuma8(img.to_luma8())
+}
@@ -0,0 +1,73 @@
+// Card projects: the card design saved as JSON so it can be prepared in advance and filled with a
+// wallet later. The wallet is only written when `include_wallet` is explicitly set.
+use crate::card::CardStyle;
+use crate::guide::{Guide, Secret};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const PROJECT_EXTENSION: &str = "gem";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    pub description: String,
+    pub from: String,
+    pub to: String,
+    pub contact: String,
+    pub amount: f32,
+    pub language: String,
+    pub card_style: CardStyle,
+    pub guide: Guide,
+    pub help_url: String,
+    pub secret: Secret,
+    // Opt-in, without it the fields below are never written
+    pub include_wallet: bool,
+    pub mnemonic: Option<String>,
+    pub address: Option<String>,
+    pub block_height: Option<u64>,
+    pub txids: Option<String>,
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Self {
+            description: "".to_string(),
+            from: "".to_string(),
+            to: "".to_string(),
+            contact: "".to_string(),
+            amount: 1.0,
+            language: "en".to_string(),
+            card_style: CardStyle::Single,
+            guide: Guide::Cake,
+            help_url: "".to_string(),
+            secret: Secret::Seed,
+            include_wallet: false,
+            mnemonic: None,
+            address: None,
+            block_height: None,
+            txids: None,
+        }
+    }
+}
+
+pub fn save(project: &Project, path: &Path) -> Result<(), String> {
+    let mut project = project.clone();
+    if !project.include_wallet {
+        project.mnemonic = None;
+        project.address = None;
+        project.block_height = None;
+        project.txids = None;
+    }
+    let text = serde_json::to_string_pretty(&project).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| e.to_string())
+}
+
+pub fn load(path: &Path) -> Result<Project, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut project: Project =
+        serde_json::from_str(text.as_str()).map_err(|e| format!("Invalid project: {}", e))?;
+    if !project.include_wallet {
+        project.mnemonic = None;
+    }
+    Ok(project)
+}
@@ -0,0 +1,55 @@
+// QR code rendering with whole pixels per module and a quiet zone, so printed codes stay scannable.
+use image::{DynamicImage, GrayImage, Luma};
+use qrcode::{EcLevel, QrCode};
+
+// Quiet zone required by the QR specification, in modules on each side
+const QUIET_ZONE: u32 = 4;
+
+pub const EC_LEVELS: [(EcLevel, &str); 4] = [
+    (EcLevel::L, "L (7%)"),
+    (EcLevel::M, "M (15%)"),
+    (EcLevel::Q, "Q (25%)"),
+    (EcLevel::H, "H (30%)"),
+];
+
+// Settings keep the level as its letter, "L" to "H"
+pub mod ec_level_letter {
+    use super::EC_LEVELS;
+    use qrcode::EcLevel;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(ec_level: &EcLevel, serializer: S) -> Result<S::Ok, S::Error> {
+        let name = crate::choice::name(&EC_LEVELS, *ec_level);
+        serializer.serialize_str(&name[..1])
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<EcLevel, D::Error> {
+        let letter = String::deserialize(deserializer)?;
+        EC_LEVELS
+            .iter()
+            .find(|x| x.1.split(' ').next() == Some(letter.as_str()))
+            .map(|x| x.0)
+            .ok_or_else(|| D::Error::custom("unknown QR error correction level"))
+    }
+}
+
+// Renders `data` into a `size` x `size` image. Every module is the same whole number of pixels and
+// the code is centered on white, so no module gets stretched. Returns None if the code doesn't fit.
+pub fn qr_image(data: &str, size: u32, ec_level: EcLevel) -> Option<DynamicImage> {
+    let qr_code = QrCode::with_error_correction_level(data, ec_level).ok()?;
+    let modules = qr_code.width() as u32 + QUIET_ZONE * 2;
+    let module_size = size / modules;
+    if module_size == 0 {
+        return None;
+    }
+    let qr_img = qr_code
+        .render::<Luma<u8>>()
+        .quiet_zone(true)
+        .module_dimensions(module_size, module_size)
+        .build();
+    let mut canvas = GrayImage::from_pixel(size, size, Luma([255]));
+    let offset = ((size - qr_img.width()) / 2) as i64;
+    image::imageops::overlay(&mut canvas, &qr_img, offset, offset);
+    Some(DynamicImage::ImageLuma8(canvas))
+}
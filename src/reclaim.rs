@@ -0,0 +1,153 @@
+// Reclaiming gifts that were never redeemed: the card's wallet is restored into the local
+// wallet-rpc, which checks the balance through its daemon and sweeps everything back.
+use crate::uri::WalletUri;
+use crate::verify;
+use crate::wallet_rpc::{transfer_from_result, Transfer, WalletRpc};
+use monero::Address;
+use serde_json::json;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone)]
+pub struct CardWallet {
+    pub address: String,
+    pub secret: CardSecret,
+    pub height: u64,
+}
+
+// What the card restores from, key based cards have no seed
+#[derive(Clone)]
+pub enum CardSecret {
+    Seed(String),
+    Keys { spend_key: String, view_key: String },
+}
+
+pub struct Balance {
+    // Both in piconero
+    pub balance: u64,
+    pub unlocked: u64,
+}
+
+// Reads the "monero_wallet:<address>?seed=...&height=..." payload of the big QR code, or its
+// spend_key/view_key form. The seed is used when the card has both.
+pub fn parse_wallet_uri(uri: &str) -> Result<CardWallet, String> {
+    let wallet = WalletUri::parse(uri)?;
+    let secret = match (wallet.seed, wallet.spend_key, wallet.view_key) {
+        (Some(seed), _, _) => CardSecret::Seed(seed),
+        (None, Some(spend_key), Some(view_key)) => CardSecret::Keys {
+            spend_key,
+            view_key,
+        },
+        _ => {
+            return Err("Wallet QR code has no seed or keys".to_string());
+        }
+    };
+    // The address also names the wallet file in wallet-rpc
+    Address::from_str(wallet.address.as_str())
+        .map_err(|_| "Wallet QR code has no valid address")?;
+    Ok(CardWallet {
+        address: wallet.address,
+        secret,
+        height: wallet.height.unwrap_or(0),
+    })
+}
+
+// Loads a saved card image (or a photo of it), or a text file holding the wallet QR payload
+pub fn load_card(path: &Path) -> Result<CardWallet, String> {
+    let is_text = path
+        .extension()
+        .map(|x| x.eq_ignore_ascii_case("txt"))
+        .unwrap_or(false);
+    if is_text {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        return parse_wallet_uri(text.as_str());
+    }
+    let img = image::open(path).map_err(|e| e.to_string())?;
+    verify::decode_all(&img)
+        .iter()
+        .find_map(|x| parse_wallet_uri(x).ok())
+        .ok_or("No wallet QR code found on the card".to_string())
+}
+
+// wallet-rpc acts on whatever wallet it has open, which may be the user's hot wallet. Anything
+// that moves money out of a card wallet checks first that the card's wallet is the open one.
+fn check_open_wallet(wallet: &WalletRpc, card: &CardWallet) -> Result<(), String> {
+    if wallet.address()? != card.address {
+        return Err("wallet-rpc has another wallet open than the card's, nothing sent".to_string());
+    }
+    Ok(())
+}
+
+// Restores (or reopens) the card's wallet in wallet-rpc, refreshes from its height and
+// returns the balance of the whole wallet
+pub fn restore_and_balance(wallet: &WalletRpc, card: &CardWallet) -> Result<Balance, String> {
+    let filename = format!(
+        "gem-reclaim-{}",
+        card.address.chars().take(12).collect::<String>()
+    );
+    let restored = match &card.secret {
+        CardSecret::Seed(seed) => wallet.call(
+            "restore_deterministic_wallet",
+            json!({
+                "filename": filename,
+                "seed": seed,
+                "restore_height": card.height,
+                "password": "",
+                "autosave_current": true
+            }),
+        ),
+        CardSecret::Keys {
+            spend_key,
+            view_key,
+        } => wallet.call(
+            "generate_from_keys",
+            json!({
+                "filename": filename,
+                "address": card.address,
+                "spendkey": spend_key,
+                "viewkey": view_key,
+                "restore_height": card.height,
+                "password": "",
+                "autosave_current": true
+            }),
+        ),
+    };
+    match restored {
+        Ok(_) => {}
+        // Already restored before, just open it again
+        Err(e) if e.contains("already exists") => {
+            wallet.call(
+                "open_wallet",
+                json!({ "filename": filename, "password": "" }),
+            )?;
+        }
+        Err(e) => {
+            return Err(format!("Couldn't restore the card wallet: {}", e));
+        }
+    }
+    check_open_wallet(wallet, card)?;
+    wallet.call("refresh", json!({ "start_height": card.height }))?;
+    let result = wallet.call("get_balance", json!({ "account_index": 0 }))?;
+    Ok(Balance {
+        balance: result["balance"].as_u64().unwrap_or(0),
+        unlocked: result["unlocked_balance"].as_u64().unwrap_or(0),
+    })
+}
+
+// Sweeps every unlocked output of the card's wallet to `to`, then closes the card wallet so
+// nothing else (like "Fund Card") spends from it by accident
+pub fn sweep(wallet: &WalletRpc, card: &CardWallet, to: &str) -> Result<Transfer, String> {
+    Address::from_str(to.trim()).map_err(|_| "Invalid destination address")?;
+    check_open_wallet(wallet, card)?;
+    let result = wallet.call(
+        "sweep_all",
+        json!({
+            "address": to.trim(),
+            "account_index": 0,
+            "subaddr_indices_all": true
+        }),
+    )?;
+    let transfer = transfer_from_result(&result).map_err(|_| "Nothing to sweep".to_string())?;
+    let _ = wallet.call("close_wallet", json!({ "autosave_current": true }));
+    Ok(transfer)
+}
@@ -0,0 +1,89 @@
+// App settings and last used card fields, kept in <config dir>/gem/settings.json
+// ($XDG_CONFIG_HOME or ~/.config on Linux). Secrets (mnemonic, keys, entropy, shares) never go here.
+use crate::card::CardStyle;
+use crate::duplex::Flip;
+use crate::guide::{Guide, Secret};
+use crate::preview::{Paper, Theme};
+use qrcode::EcLevel;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub node_url: String,
+    pub proxy: String,
+    pub wallet_rpc_url: String,
+    pub currency: String,
+    pub language: String,
+    pub from: String,
+    pub contact: String,
+    pub value_xmr: f32,
+    pub auto_wallet: bool,
+    #[serde(with = "crate::qr::ec_level_letter")]
+    pub qr_ec_level: EcLevel,
+    pub export_format: String,
+    // Path of a user picked font used when MoneroGothic lacks a glyph
+    pub fallback_font: String,
+    pub card_style: CardStyle,
+    pub duplex_flip: Flip,
+    pub guide: Guide,
+    pub help_url: String,
+    pub payment_uri: bool,
+    pub secret: Secret,
+    pub theme: Theme,
+    pub paper: Paper,
+    pub screen_dpi: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            node_url: crate::daemon::DEFAULT_NODE.to_string(),
+            proxy: "".to_string(),
+            wallet_rpc_url: crate::wallet_rpc::DEFAULT_WALLET_RPC.to_string(),
+            currency: "usd".to_string(),
+            language: "en".to_string(),
+            from: "".to_string(),
+            contact: "".to_string(),
+            value_xmr: 150.0,
+            auto_wallet: true,
+            qr_ec_level: EcLevel::H,
+            export_format: "jpg".to_string(),
+            fallback_font: "".to_string(),
+            card_style: CardStyle::Single,
+            duplex_flip: Flip::LongEdge,
+            guide: Guide::Cake,
+            help_url: "".to_string(),
+            payment_uri: false,
+            secret: Secret::Seed,
+            theme: Theme::Dark,
+            paper: Paper::A4,
+            screen_dpi: 96.0,
+        }
+    }
+}
+
+pub fn path() -> Option<PathBuf> {
+    dirs::config_dir().map(|x| x.join("gem").join("settings.json"))
+}
+
+// Missing or broken settings files just give the defaults
+pub fn load() -> Settings {
+    let text = match path().map(std::fs::read_to_string) {
+        Some(Ok(text)) => text,
+        _ => {
+            return Settings::default();
+        }
+    };
+    serde_json::from_str(text.as_str()).unwrap_or_default()
+}
+
+pub fn save(settings: &Settings) -> Result<(), String> {
+    let path = path().ok_or("No config directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let text = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| e.to_string())
+}
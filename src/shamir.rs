@@ -0,0 +1,228 @@
+// Shamir secret sharing over GF(256), used to split a gift wallet's mnemonic across several cards.
+// Every byte of the secret is split independently with a random polynomial of degree threshold - 1,
+// evaluated at the share index (1..=total). Any `threshold` shares recover the secret via Lagrange
+// interpolation at x = 0.
+use rand::RngCore;
+
+const SHARE_PREFIX: &str = "gem-share:";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Share {
+    pub index: u8,
+    pub threshold: u8,
+    pub total: u8,
+    pub data: Vec<u8>,
+}
+
+impl Share {
+    // Text form used for the share QR code, e.g. "gem-share:2-3-5:0a1b..."
+    pub fn encode(&self) -> String {
+        format!(
+            "{}{}-{}-{}:{}",
+            SHARE_PREFIX,
+            self.index,
+            self.threshold,
+            self.total,
+            hex::encode(&self.data)
+        )
+    }
+
+    pub fn decode(text: &str) -> Result<Share, String> {
+        let text = text.trim();
+        let rest = text
+            .strip_prefix(SHARE_PREFIX)
+            .ok_or("Share must start with \"gem-share:\"")?;
+        let (header, data) = rest.split_once(':').ok_or("Share is missing its data")?;
+        let header = header
+            .split('-')
+            .map(|x| x.parse::<u8>())
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| "Share header is invalid")?;
+        if header.len() != 3 {
+            return Err("Share header is invalid".to_string());
+        }
+        let data = hex::decode(data).map_err(|_| "Share data is not valid hex")?;
+        let share = Share {
+            index: header[0],
+            threshold: header[1],
+            total: header[2],
+            data,
+        };
+        if share.index == 0 || share.threshold == 0 || share.threshold > share.total {
+            return Err("Share header is invalid".to_string());
+        }
+        Ok(share)
+    }
+}
+
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    // Multiplication modulo the AES polynomial x^8 + x^4 + x^3 + x + 1
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inv(a: u8) -> u8 {
+    // a^254 == a^-1 in GF(256)
+    let mut result = 1;
+    for _ in 0..254 {
+        result = gf_mul(result, a);
+    }
+    result
+}
+
+pub fn split(secret: &[u8], threshold: u8, total: u8) -> Result<Vec<Share>, String> {
+    if threshold < 2 {
+        return Err("Threshold must be at least 2".to_string());
+    }
+    if threshold > total {
+        return Err("Threshold can't be bigger than the number of shares".to_string());
+    }
+    let mut rng = rand::thread_rng();
+    let mut shares: Vec<Share> = (1..=total)
+        .map(|index| Share {
+            index,
+            threshold,
+            total,
+            data: Vec::with_capacity(secret.len()),
+        })
+        .collect();
+    let mut coefficients = vec![0u8; threshold as usize];
+    for byte in secret {
+        coefficients[0] = *byte;
+        rng.fill_bytes(&mut coefficients[1..]);
+        for share in shares.iter_mut() {
+            // Horner's method
+            let mut y = 0;
+            for coefficient in coefficients.iter().rev() {
+                y = gf_mul(y, share.index) ^ coefficient;
+            }
+            share.data.push(y);
+        }
+    }
+    Ok(shares)
+}
+
+pub fn combine(shares: &[Share]) -> Result<Vec<u8>, String> {
+    let first = shares.first().ok_or("No shares given")?;
+    let mut used: Vec<&Share> = Vec::new();
+    for share in shares {
+        if share.threshold != first.threshold
+            || share.total != first.total
+            || share.data.len() != first.data.len()
+        {
+            return Err("Shares don't belong to the same wallet".to_string());
+        }
+        if !used.iter().any(|x| x.index == share.index) {
+            used.push(share);
+        }
+    }
+    if used.len() < first.threshold as usize {
+        return Err(format!(
+            "{} of {} shares are needed, only {} given",
+            first.threshold,
+            first.total,
+            used.len()
+        ));
+    }
+    let used = &used[..first.threshold as usize];
+    let mut secret = Vec::with_capacity(first.data.len());
+    for i in 0..first.data.len() {
+        let mut value = 0;
+        for (j, share_j) in used.iter().enumerate() {
+            // Lagrange basis polynomial evaluated at x = 0
+            let mut basis = 1;
+            for (m, share_m) in used.iter().enumerate() {
+                if m != j {
+                    basis = gf_mul(
+                        basis,
+                        gf_mul(share_m.index, gf_inv(share_m.index ^ share_j.index)),
+                    );
+                }
+            }
+            value ^= gf_mul(share_j.data[i], basis);
+        }
+        secret.push(value);
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"sequence atlas unveil summon pebbles tuesday beer rudely snake rockets";
+
+    #[test]
+    fn gf_inverse() {
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn any_threshold_shares_recover_the_secret() {
+        for (threshold, total) in [(2, 2), (2, 3), (3, 5), (4, 7), (5, 5)] {
+            let shares = split(SECRET, threshold, total).unwrap();
+            assert_eq!(shares.len(), total as usize);
+            // Every window of `threshold` consecutive shares, wrapping around
+            for start in 0..total as usize {
+                let picked = (0..threshold as usize)
+                    .map(|i| shares[(start + i) % total as usize].clone())
+                    .collect::<Vec<Share>>();
+                assert_eq!(combine(&picked).unwrap(), SECRET);
+            }
+            // More than needed works too, the surplus is ignored
+            assert_eq!(combine(&shares).unwrap(), SECRET);
+        }
+    }
+
+    #[test]
+    fn too_few_shares_are_rejected() {
+        for (threshold, total) in [(2, 3), (3, 5), (5, 5)] {
+            let shares = split(SECRET, threshold, total).unwrap();
+            assert!(combine(&shares[..threshold as usize - 1]).is_err());
+            // Repeating a share doesn't count twice
+            let mut repeated = shares[..threshold as usize - 1].to_vec();
+            repeated.push(shares[0].clone());
+            assert!(combine(&repeated).is_err());
+        }
+        assert!(combine(&[]).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_parameters_and_mixed_shares() {
+        assert!(split(SECRET, 1, 3).is_err());
+        assert!(split(SECRET, 4, 3).is_err());
+        let first = split(SECRET, 2, 3).unwrap();
+        let second = split(SECRET, 3, 5).unwrap();
+        assert!(combine(&[first[0].clone(), second[1].clone()]).is_err());
+    }
+
+    #[test]
+    fn encodes_and_decodes_shares() {
+        for share in split(SECRET, 3, 5).unwrap() {
+            assert_eq!(Share::decode(share.encode().as_str()).unwrap(), share);
+        }
+        for text in [
+            "gem-share:0-2-3:00",
+            "gem-share:1-4-3:00",
+            "gem-share:1-2:00",
+            "gem-share:1-2-3:zz",
+            "gem-share:1-2-3",
+            "share:1-2-3:00",
+        ] {
+            assert!(Share::decode(text).is_err(), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+// Shaped text for the card: bidi reordering (unicode-bidi), shaping (rustybuzz) and per character
+// font fallback, so names and messages can be written in any script the fonts cover.
+use image::{DynamicImage, GenericImage, GenericImageView, Rgba};
+use rusttype::{point, Font, GlyphId, Scale};
+use std::path::Path;
+use unicode_bidi::BidiInfo;
+
+// Common system fonts tried after the user picked one. Colour emoji fonts (Noto Color Emoji,
+// Apple/Segoe colour emoji) are bitmaps rusttype can't draw, so only outline emoji fonts are listed.
+const SYSTEM_FONTS: [&str; 16] = [
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/noto/NotoSansArabic-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansHebrew-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoEmoji-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\malgun.ttf",
+    "C:\\Windows\\Fonts\\seguisym.ttf",
+    "C:\\Windows\\Fonts\\tahoma.ttf",
+];
+
+// Fallback font files in order: the user's font first, then whatever system fonts exist
+pub fn load_fallback_fonts(user_font: &str) -> Vec<Vec<u8>> {
+    let user_font = user_font.trim();
+    let mut paths = Vec::new();
+    if !user_font.is_empty() {
+        paths.push(user_font);
+    }
+    paths.extend(SYSTEM_FONTS.iter());
+    paths
+        .iter()
+        .filter_map(|x| std::fs::read(Path::new(x)).ok())
+        .collect()
+}
+
+// True if the first strong character of the text is right-to-left (Arabic, Hebrew, ...)
+pub fn is_rtl(text: &str) -> bool {
+    BidiInfo::new(text, None)
+        .paragraphs
+        .first()
+        .map(|x| x.level.is_rtl())
+        .unwrap_or(false)
+}
+
+// Fonts in fallback order, the first one is MoneroGothic. rusttype owns its parsed copy of each
+// font file, rustybuzz borrows the file and its face is only built while a run is shaped.
+pub struct FontSet {
+    fonts: Vec<(Font<'static>, Vec<u8>)>,
+}
+
+pub struct ShapedGlyph {
+    font: usize,
+    id: u16,
+    x_advance: f32,
+    x_offset: f32,
+    y_offset: f32,
+}
+
+impl FontSet {
+    // Fonts that can't be parsed are skipped
+    pub fn new(files: Vec<Vec<u8>>) -> FontSet {
+        FontSet {
+            fonts: files
+                .into_iter()
+                .filter_map(|bytes| {
+                    rustybuzz::Face::from_slice(&bytes, 0)?;
+                    let font = Font::try_from_vec(bytes.clone())?;
+                    Some((font, bytes))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+
+    // First font with a glyph for `c`, MoneroGothic if none has one
+    fn font_for(&self, c: char) -> usize {
+        if c.is_whitespace() || c.is_control() {
+            return 0;
+        }
+        self.fonts
+            .iter()
+            .position(|(font, _)| font.glyph(c).id() != GlyphId(0))
+            .unwrap_or(0)
+    }
+
+    fn shape_run(&self, font: usize, text: &str, rtl: bool, size: f32) -> Vec<ShapedGlyph> {
+        // Checked in new(), a file that parsed once parses again
+        let face = match rustybuzz::Face::from_slice(&self.fonts[font].1, 0) {
+            Some(face) => face,
+            None => {
+                return Vec::new();
+            }
+        };
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.set_direction(if rtl {
+            rustybuzz::Direction::RightToLeft
+        } else {
+            rustybuzz::Direction::LeftToRight
+        });
+        let shaped = rustybuzz::shape(&face, &[], buffer);
+        let units = size / face.units_per_em() as f32;
+        shaped
+            .glyph_infos()
+            .iter()
+            .zip(shaped.glyph_positions())
+            .map(|(info, position)| ShapedGlyph {
+                font,
+                id: info.glyph_id as u16,
+                x_advance: position.x_advance as f32 * units,
+                x_offset: position.x_offset as f32 * units,
+                y_offset: position.y_offset as f32 * units,
+            })
+            .collect()
+    }
+
+    // One line of text in visual order, ready to measure or draw
+    pub fn shape(&self, text: &str, size: f32) -> Vec<ShapedGlyph> {
+        let mut glyphs = Vec::new();
+        if self.fonts.is_empty() {
+            return glyphs;
+        }
+        let bidi = BidiInfo::new(text, None);
+        for paragraph in &bidi.paragraphs {
+            let (levels, runs) = bidi.visual_runs(paragraph, paragraph.range.clone());
+            for run in runs {
+                let rtl = levels[run.start].is_rtl();
+                // Split the direction run further wherever the font changes. rustybuzz returns
+                // each RTL piece in visual order, the pieces themselves still need reversing.
+                let mut pieces = Vec::new();
+                let mut start = run.start;
+                let mut font = None;
+                for (i, c) in text[run.clone()].char_indices() {
+                    let i = i + run.start;
+                    let char_font = self.font_for(c);
+                    if let Some(font) = font {
+                        if font != char_font {
+                            pieces.push(self.shape_run(font, &text[start..i], rtl, size));
+                            start = i;
+                        }
+                    }
+                    font = Some(char_font);
+                }
+                if let Some(font) = font {
+                    pieces.push(self.shape_run(font, &text[start..run.end], rtl, size));
+                }
+                if rtl {
+                    pieces.reverse();
+                }
+                glyphs.extend(pieces.into_iter().flatten());
+            }
+        }
+        glyphs
+    }
+
+    pub fn width(&self, text: &str, size: f32) -> f32 {
+        self.shape(text, size).iter().map(|x| x.x_advance).sum()
+    }
+
+    // Draws one line with its top left at (x, y), like imageproc's draw_text_mut
+    pub fn draw(
+        &self,
+        img: &mut DynamicImage,
+        color: Rgba<u8>,
+        x: i32,
+        y: i32,
+        size: f32,
+        text: &str,
+    ) {
+        let scale = Scale::uniform(size);
+        let mut pen = x as f32;
+        for glyph in self.shape(text, size) {
+            let font = &self.fonts[glyph.font].0;
+            let ascent = font.v_metrics(scale).ascent;
+            let positioned = font
+                .glyph(GlyphId(glyph.id))
+                .scaled(scale)
+                .positioned(point(
+                    pen + glyph.x_offset,
+                    y as f32 + ascent - glyph.y_offset,
+                ));
+            if let Some(bounds) = positioned.pixel_bounding_box() {
+                positioned.draw(|gx, gy, coverage| {
+                    let px = bounds.min.x + gx as i32;
+                    let py = bounds.min.y + gy as i32;
+                    if px < 0 || py < 0 || px >= img.width() as i32 || py >= img.height() as i32 {
+                        return;
+                    }
+                    let mut pixel = img.get_pixel(px as u32, py as u32);
+                    for c in 0..3 {
+                        pixel.0[c] = (pixel.0[c] as f32 * (1.0 - coverage)
+                            + color.0[c] as f32 * coverage)
+                            as u8;
+                    }
+                    img.put_pixel(px as u32, py as u32, pixel);
+                });
+            }
+            pen += glyph.x_advance;
+        }
+    }
+}
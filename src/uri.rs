@@ -0,0 +1,271 @@
+// Monero URIs as described in https://github.com/monero-project/monero/wiki/URI-Formatting:
+// "monero:" payment requests and the "monero_wallet:" payload of the big QR code.
+use crate::funding::xmr_to_piconero;
+use std::path::{Component, Path};
+
+pub const WALLET_SCHEME: &str = "monero_wallet:";
+
+// Everything a monero_wallet: URI can carry. The keys are hex, the TXIDs are the funding
+// transactions (manual mode).
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct WalletUri {
+    pub address: String,
+    pub seed: Option<String>,
+    pub spend_key: Option<String>,
+    pub view_key: Option<String>,
+    pub height: Option<u64>,
+    pub txids: Vec<String>,
+}
+
+impl WalletUri {
+    pub fn to_uri(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(seed) = &self.seed {
+            params.push(format!("seed={}", percent_encode(seed.trim())));
+        }
+        if let Some(spend_key) = &self.spend_key {
+            params.push(format!("spend_key={}", percent_encode(spend_key.trim())));
+        }
+        if let Some(view_key) = &self.view_key {
+            params.push(format!("view_key={}", percent_encode(view_key.trim())));
+        }
+        if let Some(height) = self.height {
+            params.push(format!("height={}", height));
+        }
+        if !self.txids.is_empty() {
+            let txids = self
+                .txids
+                .iter()
+                .map(|x| percent_encode(x.trim()))
+                .collect::<Vec<String>>();
+            params.push(format!("txid={}", txids.join(",")));
+        }
+        let address = percent_encode(self.address.trim());
+        if params.is_empty() {
+            return format!("{}{}", WALLET_SCHEME, address);
+        }
+        format!("{}{}?{}", WALLET_SCHEME, address, params.join("&"))
+    }
+
+    // Also takes the names other wallets use (mnemonic_seed, txids) and ignores unknown ones
+    pub fn parse(uri: &str) -> Result<WalletUri, String> {
+        let rest = uri
+            .trim()
+            .strip_prefix(WALLET_SCHEME)
+            .ok_or("Not a monero_wallet: URI")?;
+        let (address, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let mut wallet = WalletUri {
+            address: percent_decode(address)?,
+            ..Default::default()
+        };
+        for param in query.split('&').filter(|x| !x.is_empty()) {
+            let (name, value) = param.split_once('=').unwrap_or((param, ""));
+            let value = percent_decode(value)?;
+            match name {
+                "seed" | "mnemonic_seed" => wallet.seed = Some(value),
+                "spend_key" => wallet.spend_key = Some(value),
+                "view_key" => wallet.view_key = Some(value),
+                "height" => {
+                    let height = value.parse().map_err(|_| "Invalid height in wallet URI")?;
+                    wallet.height = Some(height);
+                }
+                "txid" | "txids" => wallet.txids.extend(
+                    value
+                        .split(',')
+                        .filter(|x| !x.trim().is_empty())
+                        .map(|x| x.trim().to_string()),
+                ),
+                _ => {}
+            }
+        }
+        Ok(wallet)
+    }
+}
+
+// Percent-encodes everything outside the RFC 3986 unreserved characters, byte by byte in UTF-8
+pub fn percent_encode(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(format!("%{:02X}", byte).as_str()),
+        }
+    }
+    encoded
+}
+
+// Reverses percent_encode. '+' is read as a space, like in form encoded queries.
+pub fn percent_decode(text: &str) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    let mut input = text.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'%' => {
+                let hex = [
+                    input.next().ok_or("Truncated percent escape")?,
+                    input.next().ok_or("Truncated percent escape")?,
+                ];
+                // from_str_radix alone would also take a sign, like "%+5"
+                if !hex.iter().all(|x| x.is_ascii_hexdigit()) {
+                    return Err("Invalid percent escape".to_string());
+                }
+                let hex = std::str::from_utf8(&hex).map_err(|_| "Invalid percent escape")?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| "Invalid percent escape")?);
+            }
+            b'+' => bytes.push(b' '),
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| "URI is not valid UTF-8".to_string())
+}
+
+// Decimal XMR without trailing zeros, e.g. 1.5 or 0.000001
+pub fn format_amount(piconero: u64) -> String {
+    let whole = piconero / 1_000_000_000_000;
+    let fraction = piconero % 1_000_000_000_000;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:012}", fraction);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+// "monero:<address>?tx_amount=<xmr>&tx_description=<text>", so scanning it pre-fills the payment.
+// An empty amount or description is left out.
+pub fn payment_uri(address: &str, amount: f32, description: &str) -> String {
+    let mut params = Vec::new();
+    let piconero = xmr_to_piconero(amount);
+    if piconero > 0 {
+        params.push(format!("tx_amount={}", format_amount(piconero)));
+    }
+    if !description.trim().is_empty() {
+        params.push(format!(
+            "tx_description={}",
+            percent_encode(description.trim())
+        ));
+    }
+    if params.is_empty() {
+        return format!("monero:{}", address);
+    }
+    format!("monero:{}?{}", address, params.join("&"))
+}
+
+// A file:/// URI for a local file, forward slashes and every name percent-encoded. The drive of
+// a Windows path stays as it is ("file:///C:/...").
+pub fn file_uri(path: &Path) -> String {
+    let segments = path
+        .components()
+        .filter_map(|x| match x {
+            Component::Prefix(prefix) => {
+                Some(prefix.as_os_str().to_string_lossy().replace('\\', "/"))
+            }
+            Component::RootDir => None,
+            other => Some(percent_encode(&other.as_os_str().to_string_lossy())),
+        })
+        .collect::<Vec<String>>();
+    format!("file:///{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A";
+
+    #[test]
+    fn round_trips_non_ascii_seeds() {
+        let seeds = [
+            "あいこくしん あいさつ あいだ あおぞら あかちゃん あきる あけがた あける",
+            "абажур абзац абонент абрикос абсурд авангард август авиация",
+            "ábaco abdomen abeja abierto abogado abono aborto abrazo",
+        ];
+        for seed in seeds {
+            let wallet = WalletUri {
+                address: ADDRESS.to_string(),
+                seed: Some(seed.to_string()),
+                height: Some(3_000_000),
+                ..Default::default()
+            };
+            let uri = wallet.to_uri();
+            assert!(uri.is_ascii());
+            assert!(!uri.contains(' '));
+            assert_eq!(WalletUri::parse(uri.as_str()).unwrap(), wallet);
+        }
+    }
+
+    #[test]
+    fn round_trips_keys_height_and_txids() {
+        let wallet = WalletUri {
+            address: ADDRESS.to_string(),
+            seed: None,
+            spend_key: Some(
+                "e5f4301d32f3bdaef814a835a18aaaa24b13cc76cf01a832a7852faf9322e907".to_string(),
+            ),
+            view_key: Some(
+                "bcfdda53205318e1c14fa0ddca1a45df363bb427972981d0249d0f4652a7df07".to_string(),
+            ),
+            height: Some(2_950_123),
+            txids: vec!["ab".repeat(32), "cd".repeat(32), "ef".repeat(32)],
+        };
+        let uri = wallet.to_uri();
+        assert!(uri.starts_with(WALLET_SCHEME));
+        assert_eq!(WalletUri::parse(uri.as_str()).unwrap(), wallet);
+        let bare = WalletUri {
+            address: ADDRESS.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(bare.to_uri(), format!("{}{}", WALLET_SCHEME, ADDRESS));
+        assert_eq!(WalletUri::parse(bare.to_uri().as_str()).unwrap(), bare);
+    }
+
+    #[test]
+    fn parses_aliases() {
+        let uri = format!(
+            "{}{}?mnemonic_seed=one+two%20three&txids=aa,%20bb,&unknown=1&txid=cc",
+            WALLET_SCHEME, ADDRESS
+        );
+        let wallet = WalletUri::parse(uri.as_str()).unwrap();
+        assert_eq!(wallet.seed.as_deref(), Some("one two three"));
+        assert_eq!(wallet.txids, vec!["aa", "bb", "cc"]);
+        assert_eq!(wallet.height, None);
+        assert!(WalletUri::parse("monero:44abc").is_err());
+        assert!(WalletUri::parse(format!("{}44abc?height=soon", WALLET_SCHEME).as_str()).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_escapes() {
+        for text in ["%", "%4", "abc%", "%zz", "%+5", "%-1", "%C3", "%FF%FE"] {
+            assert!(percent_decode(text).is_err(), "{}", text);
+        }
+        let uri = format!("{}{}?seed=a%2", WALLET_SCHEME, ADDRESS);
+        assert!(WalletUri::parse(uri.as_str()).is_err());
+        assert_eq!(percent_decode("%c3%a1%20x").unwrap(), "á x");
+        assert_eq!(percent_encode("á x~"), "%C3%A1%20x~");
+    }
+
+    #[test]
+    fn builds_payment_uris() {
+        assert_eq!(payment_uri("44abc", 0.0, " "), "monero:44abc");
+        assert_eq!(
+            payment_uri("44abc", 1.5, "Happy birthday!"),
+            "monero:44abc?tx_amount=1.5&tx_description=Happy%20birthday%21"
+        );
+        assert_eq!(format_amount(1), "0.000000000001");
+        assert_eq!(format_amount(2_000_000_000_000), "2");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn builds_file_uris() {
+        assert_eq!(
+            file_uri(Path::new("/home/alice/gem cards/gem-redeem.html")),
+            "file:///home/alice/gem%20cards/gem-redeem.html"
+        );
+        assert_eq!(
+            file_uri(Path::new("/tmp/Привет#1.html")),
+            "file:///tmp/%D0%9F%D1%80%D0%B8%D0%B2%D0%B5%D1%82%231.html"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+// Decodes the QR codes back from a rendered card, so a card is only saved if it actually scans.
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::{DynamicImage, ImageEncoder};
+
+// Where the QR codes are pasted on the card, see card::render_card
+pub const QR_MAIN_REGION: (u32, u32, u32, u32) = (615, 55, 350, 350);
+pub const QR_ADDR_REGION: (u32, u32, u32, u32) = (620, 425, 150, 150);
+// The fold-and-seal card moves the address QR to the panel that stays visible
+pub const FOLD_ADDR_REGION: (u32, u32, u32, u32) = (400, 425, 150, 150);
+
+pub fn decode_region(img: &DynamicImage, region: (u32, u32, u32, u32)) -> Result<String, String> {
+    let (x, y, width, height) = region;
+    let luma = img.crop_imm(x, y, width, height).to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare(luma);
+    let grids = prepared.detect_grids();
+    let grid = grids.first().ok_or("No QR code found")?;
+    match grid.decode() {
+        Ok((_, content)) => Ok(content),
+        Err(e) => Err(format!("QR code can't be decoded: {:?}", e)),
+    }
+}
+
+// Every QR code that can be decoded anywhere in the image, e.g. a scanned or photographed card
+pub fn decode_all(img: &DynamicImage) -> Vec<String> {
+    let mut prepared = rqrr::PreparedImage::prepare(img.to_luma8());
+    prepared
+        .detect_grids()
+        .iter()
+        .filter_map(|x| x.decode().ok().map(|(_, content)| content))
+        .collect()
+}
+
+pub fn verify_card(
+    img: &DynamicImage,
+    expected_main: &str,
+    expected_addr: &str,
+    addr_region: (u32, u32, u32, u32),
+) -> Result<(), String> {
+    let main = decode_region(img, QR_MAIN_REGION).map_err(|e| format!("Wallet {}", e))?;
+    if main != expected_main {
+        return Err("Wallet QR code doesn't match the wallet".to_string());
+    }
+    let addr = decode_region(img, addr_region).map_err(|e| format!("Address {}", e))?;
+    if addr != expected_addr {
+        return Err("Address QR code doesn't match the address".to_string());
+    }
+    Ok(())
+}
+
+pub fn encode(img: &DynamicImage, format: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let rgb = img.to_rgb8();
+    if format == "png" {
+        PngEncoder::new(&mut bytes)
+            .write_image(
+                rgb.as_raw(),
+                rgb.width(),
+                rgb.height(),
+                image::ColorType::Rgb8,
+            )
+            .map_err(|e| e.to_string())?;
+    } else {
+        JpegEncoder::new_with_quality(&mut bytes, 90)
+            .encode_image(&rgb)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(bytes)
+}
+
+// Encodes the card as JPEG or PNG and verifies the QR codes on the encoded result, so JPEG
+// compression is covered too. Returns the file bytes.
+pub fn encode_verified(
+    img: &DynamicImage,
+    format: &str,
+    expected_main: &str,
+    expected_addr: &str,
+    addr_region: (u32, u32, u32, u32),
+) -> Result<Vec<u8>, String> {
+    let bytes = encode(img, format)?;
+    let decoded = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+    verify_card(&decoded, expected_main, expected_addr, addr_region)?;
+    Ok(bytes)
+}
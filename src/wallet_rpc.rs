@@ -0,0 +1,101 @@
+// Optional integration with a local monero-wallet-rpc (started with --disable-rpc-login), used as a
+// hot wallet to fund cards without leaving Gem.
+use serde_json::json;
+
+pub const DEFAULT_WALLET_RPC: &str = "http://127.0.0.1:18083";
+
+pub struct Transfer {
+    pub txids: Vec<String>,
+    // Both in piconero
+    pub amount: u64,
+    pub fee: u64,
+}
+
+pub struct WalletRpc {
+    pub url: String,
+}
+
+fn sum_list(value: &serde_json::Value) -> u64 {
+    match value.as_array() {
+        Some(list) => list.iter().filter_map(|x| x.as_u64()).sum(),
+        None => 0,
+    }
+}
+
+impl WalletRpc {
+    pub fn new(url: &str) -> WalletRpc {
+        WalletRpc {
+            url: url.trim().trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn call(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let resp = ureq::post(format!("{}/json_rpc", self.url).as_str())
+            .set("Content-Type", "application/json")
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": "0",
+                "method": method,
+                "params": params
+            }))
+            .map_err(|e| e.to_string())?;
+        let resp: serde_json::Value = resp.into_json().map_err(|e| e.to_string())?;
+        if let Some(error) = resp.get("error") {
+            return Err(format!("Wallet error: {}", error["message"]));
+        }
+        Ok(resp["result"].clone())
+    }
+
+    // Primary address of the wallet wallet-rpc currently has open
+    pub fn address(&self) -> Result<String, String> {
+        let result = self.call("get_address", json!({ "account_index": 0 }))?;
+        result["address"]
+            .as_str()
+            .map(|x| x.to_string())
+            .ok_or("Wallet didn't return its address".to_string())
+    }
+
+    // Sends to every (address, piconero) destination in one go. transfer_split lets the wallet
+    // use several transactions if a single one would get too big, e.g. when funding many cards.
+    pub fn transfer(&self, destinations: &[(String, u64)]) -> Result<Transfer, String> {
+        if destinations.is_empty() {
+            return Err("No destinations to fund".to_string());
+        }
+        let destinations = destinations
+            .iter()
+            .map(|(address, amount)| json!({ "address": address, "amount": amount }))
+            .collect::<Vec<serde_json::Value>>();
+        let result = self.call(
+            "transfer_split",
+            json!({
+                "destinations": destinations,
+                "priority": 0,
+                "get_tx_hex": false
+            }),
+        )?;
+        transfer_from_result(&result)
+    }
+}
+
+// Reads the tx_hash_list / amount_list / fee_list answer of transfer_split and sweep_all
+pub fn transfer_from_result(result: &serde_json::Value) -> Result<Transfer, String> {
+    let txids = match result["tx_hash_list"].as_array() {
+        Some(list) => list
+            .iter()
+            .filter_map(|x| x.as_str().map(|x| x.to_string()))
+            .collect::<Vec<String>>(),
+        None => Vec::new(),
+    };
+    if txids.is_empty() {
+        return Err("Wallet didn't return a transaction".to_string());
+    }
+    Ok(Transfer {
+        txids,
+        amount: sum_list(&result["amount_list"]),
+        fee: sum_list(&result["fee_list"]),
+    })
+}
@@ -0,0 +1,40 @@
+// View-only "watch" exports for the gifter: address, private view key and restore height, enough
+// to follow the gift wallet without being able to spend it. The JSON is the view-only details
+// format Feather shows and scans (Wallet > Keys > View-only), so the QR code imports directly.
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewOnly {
+    pub version: u32,
+    pub primary_address: String,
+    pub private_view_key: String,
+    pub restore_height: u64,
+    pub wallet_name: String,
+}
+
+impl ViewOnly {
+    pub fn new(address: &str, view_key: &str, height: u64) -> Result<ViewOnly, String> {
+        if view_key.is_empty() {
+            return Err("A 25 word mnemonic is needed for the view key".to_string());
+        }
+        Ok(ViewOnly {
+            version: 0,
+            primary_address: address.to_string(),
+            private_view_key: view_key.to_string(),
+            restore_height: height,
+            wallet_name: format!("gem-watch-{}", address.chars().take(8).collect::<String>()),
+        })
+    }
+
+    // Compact JSON for the QR code
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+pub fn save_json(view_only: &ViewOnly, path: &Path) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(view_only).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| e.to_string())
+}
@@ -0,0 +1,123 @@
+// Monero's original 1626 word seed wordlists, one word per line in src/wordlists/. They are the
+// lists of https://github.com/monero-project/monero/tree/master/src/mnemonics
+// (Copyright (c) 2014-2024, The Monero Project, BSD-3-Clause), as shipped in libmonero 0.1.9,
+// which decodes seeds with them but doesn't export them.
+pub struct Wordset {
+    pub language: &'static str,
+    // Leading characters that identify a word, the checksum word is picked from these
+    pub prefix_len: usize,
+    words: &'static str,
+}
+
+impl Wordset {
+    pub fn words(&self) -> Vec<&'static str> {
+        self.words.lines().collect()
+    }
+}
+
+// In libmonero's order, a seed whose words are in several lists is decoded with the first one.
+// libmonero also has Japanese and Russian, but cuts their prefixes by bytes instead of characters
+// and decodes those seeds to the wrong keys, so they are left out.
+pub static WORDSETS: [Wordset; 6] = [
+    Wordset {
+        language: "en",
+        prefix_len: 3,
+        words: include_str!("wordlists/english.txt"),
+    },
+    Wordset {
+        language: "eo",
+        prefix_len: 4,
+        words: include_str!("wordlists/esperanto.txt"),
+    },
+    Wordset {
+        language: "fr",
+        prefix_len: 4,
+        words: include_str!("wordlists/french.txt"),
+    },
+    Wordset {
+        language: "it",
+        prefix_len: 4,
+        words: include_str!("wordlists/italian.txt"),
+    },
+    Wordset {
+        language: "lj",
+        prefix_len: 4,
+        words: include_str!("wordlists/lojban.txt"),
+    },
+    Wordset {
+        language: "pt",
+        prefix_len: 4,
+        words: include_str!("wordlists/portuguese.txt"),
+    },
+];
+
+pub fn wordset(language: &str) -> Option<&'static Wordset> {
+    WORDSETS.iter().find(|x| x.language == language)
+}
+
+// Index of the word the checksum word repeats: the CRC32 of the first 24 words' prefixes
+pub fn checksum_index(words: &[String], wordset: &Wordset) -> usize {
+    let prefixes = words[..24]
+        .iter()
+        .map(|x| x.chars().take(wordset.prefix_len).collect::<String>())
+        .collect::<String>();
+    crc32fast::hash(prefixes.as_bytes()) as usize % 24
+}
+
+// Checks a seed before libmonero decodes it, libmonero panics on words it doesn't know. All 25
+// words have to be in one wordset and the last one has to be the checksum word.
+pub fn check_mnemonic(words: &[String]) -> Result<(), String> {
+    if words.len() != 25 {
+        return Err(format!("A seed has 25 words, not {}", words.len()));
+    }
+    let wordset = WORDSETS.iter().find(|x| {
+        let list = x.words();
+        words.iter().all(|word| list.contains(&word.as_str()))
+    });
+    let wordset = match wordset {
+        Some(wordset) => wordset,
+        None => {
+            let unknown = words
+                .iter()
+                .find(|word| !WORDSETS.iter().any(|x| x.words().contains(&word.as_str())));
+            return Err(match unknown {
+                Some(word) => format!("\"{}\" isn't a seed word of a supported language", word),
+                None => "The seed mixes words of different languages".to_string(),
+            });
+        }
+    };
+    let prefix = |word: &String| word.chars().take(wordset.prefix_len).collect::<String>();
+    if prefix(&words[24]) != prefix(&words[checksum_index(words, wordset)]) {
+        return Err("The last seed word doesn't match the checksum, check for typos".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_generated_seeds() {
+        for wordset in WORDSETS.iter() {
+            let words = libmonero::keys::generate_seed(wordset.language, "original");
+            assert_eq!(check_mnemonic(&words), Ok(()), "{}", wordset.language);
+        }
+    }
+
+    #[test]
+    fn rejects_typos_and_bad_checksums() {
+        let words = libmonero::keys::generate_seed("en", "original");
+        assert!(check_mnemonic(&words[..24]).is_err());
+        let mut typo = words.clone();
+        typo[3] = "abbeyy".to_string();
+        assert!(check_mnemonic(&typo).unwrap_err().contains("abbeyy"));
+        // Another word with a different prefix in the checksum position
+        let mut checksum = words.clone();
+        checksum[24] = if words[24] == "zoom" { "abbey" } else { "zoom" }.to_string();
+        assert!(check_mnemonic(&checksum).is_err());
+        let mut mixed = words;
+        mixed[0] = "abbinare".to_string();
+        assert!(check_mnemonic(&mixed).is_err());
+    }
+}